@@ -2,31 +2,66 @@
 
 extern crate alloc;
 extern crate rustc_serialize;
+#[cfg(feature = "rayon")]
+extern crate rayon;
 
 use self::alloc::heap;
 use self::rustc_serialize::{Decodable, Encodable, Decoder, Encoder};
-use std::mem;
+#[cfg(feature = "rayon")]
+use self::rayon::prelude::*;
+use std::mem::{self, MaybeUninit};
 use std::ptr::{self, Unique};
 use std::iter::{self, Iterator};
 use std::marker::PhantomData;
 use std::slice;
-use std::ops::{Index, IndexMut};
+use std::ops::{Deref, Index, IndexMut, Range};
 use std::fmt;
 use std::cmp::{self, Ordering};
+use std::collections::HashMap;
+use std::hash::Hash;
 
 /// A 2d array whose size is determined at runtime and fixed at construction.
 /// Elements are stored in row-major order.
+///
+/// The allocation always holds exactly `width * height` elements, so an array occupies
+/// `width * height * size_of::<T>()` bytes of heap memory and never has spare capacity.
 pub struct Array2<T> {
     ptr: Unique<T>,
     width: u32,
     height: u32
 }
 
+/// Describes how `Array2::build` initializes the elements of a new allocation.
+enum Init<'a, T: 'a> {
+    /// The elements are left uninitialized and must be written before they are read or dropped.
+    Uninit,
+    /// Each element is set to the result of calling the function, in row-major order.
+    With(&'a mut FnMut() -> T),
+    /// The elements are copied bitwise from a slice of exactly `width * height` elements.
+    /// Only sound if `T` is `Copy`.
+    Copy(&'a [T])
+}
+
+/// The reasons `Array2::build` can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BuildError {
+    /// The allocation would exceed the maximum allocation size.
+    Overflow,
+    /// The allocator could not provide the memory.
+    AllocFailed
+}
+
 impl<T: Default> Array2<T> {
     /// Constructs an `Array2<T>` from `width` and `height` by filling it with the default value of `T`.
     pub fn from_default(width: u32, height: u32) -> Array2<T> {
         Array2::from_fn(width, height, || T::default())
     }
+    
+    /// Moves the element at the given position out of the array, leaving the default value of `T`
+    /// in its place. Returns `None` if the position is out of bounds.
+    pub fn take<P: Point2>(&mut self, point: P) -> Option<T> {
+        self.replace(point, T::default())
+    }
 }
 
 impl<T: Clone> Array2<T> {
@@ -34,40 +69,487 @@ impl<T: Clone> Array2<T> {
     pub fn from_elem(width: u32, height: u32, element: T) -> Array2<T> {
         Array2::from_fn(width, height, || element.clone())
     }
+    
+    /// Constructs an `Array2<T>` from `width` and `height` with an alternating pattern. The element at
+    /// `(x, y)` is a clone of `a` if `x + y` is even and a clone of `b` otherwise.
+    pub fn from_checkerboard(width: u32, height: u32, a: T, b: T) -> Array2<T> {
+        Array2::from_fn_with_points(width, height, |x, y| if (x + y) % 2 == 0 { a.clone() } else { b.clone() })
+    }
+    
+    /// Constructs an `Array2<T>` from `width` and `height` by cloning `element`.
+    /// Returns `None` if the required allocation would exceed the maximum allocation size.
+    pub fn checked_from_elem(width: u32, height: u32, element: T) -> Option<Array2<T>> {
+        Array2::checked_from_fn(width, height, || element.clone())
+    }
+    
+    /// Returns the rows of the array as nested vectors. The outer vector has `height` entries,
+    /// each of which has `width` elements.
+    pub fn to_nested_vec(&self) -> Vec<Vec<T>> {
+        self.row_slices().into_iter().map(|row| row.to_vec()).collect()
+    }
+    
+    /// Sets every element of the array to a clone of `value`, dropping the previous elements.
+    /// The existing allocation is reused, which makes this cheaper than constructing a new array,
+    /// e.g. when resetting a simulation each frame.
+    pub fn clear_to(&mut self, value: T) {
+        for element in self.iter_mut() {
+            *element = value.clone();
+        }
+    }
+    
+    /// Sets every element inside the given rectangle to a clone of `value`, dropping the previous elements.
+    /// Parts of the rectangle that exceed the array bounds will be skipped, as with `view_mut`.
+    pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, value: T) {
+        for row in self.view_mut(x, y, width, height) {
+            for element in row.iter_mut() {
+                *element = value.clone();
+            }
+        }
+    }
+    
+    /// Sets every element whose counterpart in `mask` is `true` to a clone of `value`, dropping the previous elements.
+    /// Returns `Err` and leaves the array unchanged if `mask` does not have the same dimensions as the array.
+    pub fn apply_mask(&mut self, mask: &Array2<bool>, value: T) -> Result<(), ()> {
+        if mask.dimensions() != self.dimensions() {
+            return Err(());
+        }
+        for (element, &selected) in self.iter_mut().zip(mask.iter()) {
+            if selected {
+                *element = value.clone();
+            }
+        }
+        Ok(())
+    }
+    
+    /// Mirrors the left half of the array onto the right half. For odd widths, the center column is kept.
+    pub fn mirror_h_into(&mut self) {
+        let width = self.width as usize;
+        for row in self.rows_mut() {
+            for x in 0..width / 2 {
+                row[width - 1 - x] = row[x].clone();
+            }
+        }
+    }
+    
+    /// Mirrors the top half of the array onto the bottom half. For odd heights, the center row is kept.
+    pub fn mirror_v_into(&mut self) {
+        let width = self.width as usize;
+        let height = self.height as usize;
+        let slice = self.as_slice_mut();
+        for y in 0..height / 2 {
+            let (top, bottom) = slice.split_at_mut((height - 1 - y) * width);
+            bottom[..width].clone_from_slice(&top[y * width..(y + 1) * width]);
+        }
+    }
+    
+    /// Returns an array that is `sx` times wider and `sy` times taller, in which every element
+    /// is repeated to fill an `sx` by `sy` block. Scaling by 0 results in an empty array.
+    ///
+    /// # Panics
+    /// Panics if the scaled dimensions overflow `u32`.
+    pub fn scale_nearest(&self, sx: u32, sy: u32) -> Array2<T> {
+        let width = self.width.checked_mul(sx).expect("scale_nearest: width overflows u32");
+        let height = self.height.checked_mul(sy).expect("scale_nearest: height overflows u32");
+        Array2::from_fn_with_points(width, height, |x, y| self.at((x / sx, y / sy)).clone())
+    }
+    
+    /// Returns a copy of the array whose width and height are each rounded up to the next power of two.
+    /// The original elements keep their position in the top left corner and the added cells are filled
+    /// with clones of `fill`. If both dimensions already are powers of two, the result is an unchanged copy.
+    /// A dimension of 0 stays 0, so an empty array results in an empty array.
+    ///
+    /// # Panics
+    /// Panics if a rounded dimension overflows `u32`.
+    pub fn grow_to_pow2(&self, fill: T) -> Array2<T> {
+        let round = |n: u32| if n == 0 {
+            0
+        } else {
+            n.checked_next_power_of_two().expect("grow_to_pow2: dimension overflows u32")
+        };
+        let (width, height) = (round(self.width), round(self.height));
+        Array2::from_fn_with_points(width, height, |x, y| self.get(x, y).unwrap_or(&fill).clone())
+    }
+    
+    /// Returns a copy of the array in which each run of consecutive identical rows is collapsed into a single row.
+    pub fn dedup_rows(&self) -> Array2<T> where T: PartialEq {
+        let mut rows = self.row_slices();
+        rows.dedup();
+        let mut iter = rows.iter().flat_map(|row| row.iter());
+        Array2::from_fn(self.width, rows.len() as u32, || iter.next().unwrap().clone())
+    }
+    
+    /// Returns a copy of the array with all outer rows and columns removed that consist only of elements
+    /// for which `is_blank` returns true. If every element is blank, the result is a 0x0 array.
+    pub fn trim<P: Fn(&T) -> bool>(&self, is_blank: P) -> Array2<T> {
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for (x, y, _) in self.iter().with_points().filter(|&(_, _, e)| !is_blank(e)) {
+            bounds = Some(match bounds {
+                Some((x0, y0, x1, y1)) => (cmp::min(x0, x), cmp::min(y0, y), cmp::max(x1, x), cmp::max(y1, y)),
+                None => (x, y, x, y)
+            });
+        }
+        match bounds {
+            Some((x0, y0, x1, y1)) => self.sub_array(x0, y0, x1 - x0 + 1, y1 - y0 + 1),
+            None => self.sub_array(0, 0, 0, 0)
+        }
+    }
+    
+    /// Returns a copy of the array with `n` rows and columns removed from every edge, i.e. an array
+    /// of `width - 2 * n` by `height - 2 * n` elements. If `2 * n` is at least the width or the height,
+    /// the result is a 0x0 array.
+    pub fn shrink(&self, n: u32) -> Array2<T> {
+        let margin = 2 * n as u64;
+        if margin >= self.width as u64 || margin >= self.height as u64 {
+            return self.sub_array(0, 0, 0, 0);
+        }
+        self.sub_array(n, n, self.width - 2 * n, self.height - 2 * n)
+    }
+    
+    /// Returns a copy of the rectangular section of the array, clipped to the array bounds.
+    fn sub_array(&self, x: u32, y: u32, width: u32, height: u32) -> Array2<T> {
+        let (width, height) = match self.clamped_rect(x, y, width, height) {
+            Some((_, _, width, height)) if width > 0 && height > 0 => (width, height),
+            _ => (0, 0)
+        };
+        let mut iter = self.view(x, y, width, height).flat_map(|row| row.iter());
+        Array2::from_fn(width, height, || iter.next().unwrap().clone())
+    }
+}
+
+impl<T: PartialEq> Array2<T> {
+    /// Returns true if the elements of the array in row-major order equal `flat`.
+    /// Only the number of elements is compared, not the dimensions.
+    pub fn eq_slice(&self, flat: &[T]) -> bool {
+        self.as_slice() == flat
+    }
+    
+    /// Returns true if the array has as many rows as `rows`, each of which equals the corresponding row of the array.
+    pub fn eq_nested(&self, rows: &[Vec<T>]) -> bool {
+        rows.len() == self.height as usize &&
+        self.row_slices().into_iter().zip(rows.iter()).all(|(a, b)| a == &b[..])
+    }
+    
+    /// Returns the position of the first element in row-major order that equals `value`,
+    /// or `None` if there is no such element.
+    pub fn index_of(&self, value: &T) -> Option<(u32, u32)> {
+        self.iter().with_points().find(|&(_, _, e)| e == value).map(|(x, y, _)| (x, y))
+    }
+}
+
+impl<T: Eq + Hash + Clone> Array2<T> {
+    /// Returns how often each distinct element occurs in the array.
+    pub fn histogram(&self) -> HashMap<T, usize> {
+        let mut histogram = HashMap::new();
+        for e in self.iter() {
+            *histogram.entry(e.clone()).or_insert(0) += 1;
+        }
+        histogram
+    }
+}
+
+impl<T: Ord> Array2<T> {
+    /// Binary searches row `y`, which must be sorted, for `value`. Like `slice::binary_search`, returns `Ok`
+    /// with the x coordinate of a matching element, or `Err` with the x coordinate where `value` could be
+    /// inserted to keep the row sorted.
+    ///
+    /// # Panics
+    /// Panics if `y` is out of bounds.
+    pub fn row_binary_search(&self, y: u32, value: &T) -> Result<u32, u32> {
+        let range = self.row_range(y).expect("row_binary_search: row index out of bounds");
+        match self.as_slice()[range].binary_search(value) {
+            Ok(x) => Ok(x as u32),
+            Err(x) => Err(x as u32)
+        }
+    }
+    
+    /// Returns the smallest element of the array, or `None` if the array is empty.
+    /// If several elements are equally minimal, the first one in row-major order is returned.
+    pub fn min_element(&self) -> Option<&T> {
+        self.extremum_by_key(|e| e, Ordering::Less).map(|(_, _, e)| e)
+    }
+    
+    /// Returns the largest element of the array, or `None` if the array is empty.
+    /// If several elements are equally maximal, the first one in row-major order is returned.
+    pub fn max_element(&self) -> Option<&T> {
+        self.extremum_by_key(|e| e, Ordering::Greater).map(|(_, _, e)| e)
+    }
+    
+    /// Returns the position of the smallest element of the array, or `None` if the array is empty.
+    /// If several elements are equally minimal, the position of the first one in row-major order is returned.
+    pub fn argmin(&self) -> Option<(u32, u32)> {
+        self.extremum_by_key(|e| e, Ordering::Less).map(|(x, y, _)| (x, y))
+    }
+    
+    /// Returns the position of the largest element of the array, or `None` if the array is empty.
+    /// If several elements are equally maximal, the position of the first one in row-major order is returned.
+    pub fn argmax(&self) -> Option<(u32, u32)> {
+        self.extremum_by_key(|e| e, Ordering::Greater).map(|(x, y, _)| (x, y))
+    }
+}
+
+impl<T: Into<u64> + Copy> Array2<T> {
+    /// Returns the summed-area table of the array, in which each element is the sum of all elements
+    /// above and to the left of it, inclusive. Use `rect_sum` to query the sum of a rectangle.
+    pub fn summed_area_table(&self) -> Array2<u64> {
+        let width = self.width as usize;
+        let mut sums: Vec<u64> = Vec::with_capacity(self.as_slice().len());
+        for (i, &e) in self.as_slice().iter().enumerate() {
+            let (x, y) = (i % width, i / width);
+            let left = if x > 0 { sums[i - 1] } else { 0 };
+            let above = if y > 0 { sums[i - width] } else { 0 };
+            let above_left = if x > 0 && y > 0 { sums[i - width - 1] } else { 0 };
+            sums.push(e.into() + left + above - above_left);
+        }
+        let mut iter = sums.into_iter();
+        Array2::from_fn(self.width, self.height, || iter.next().unwrap())
+    }
+}
+
+impl<T: Copy> Array2<T> {
+    /// Returns a copy of the array. The elements are copied in bulk instead of one by one.
+    pub fn copy(&self) -> Array2<T> {
+        Array2::build_or_exit(self.width, self.height, Init::Copy(self.as_slice()))
+    }
+}
+
+impl<T> Array2<MaybeUninit<T>> {
+    /// Converts an array created by `new_uninit` into an `Array2<T>` without touching the elements.
+    ///
+    /// # Safety
+    /// Every element must have been initialized, for example through `as_slice_mut` or `get_mut`.
+    /// Calling this while any element is still uninitialized is undefined behavior, because the
+    /// resulting array reads and drops all of its elements as valid values of `T`.
+    pub unsafe fn assume_init(self) -> Array2<T> {
+        let (ptr, width, height) = (*self.ptr as *mut T, self.width, self.height);
+        mem::forget(self);
+        Array2 { ptr: Unique::new(ptr), width: width, height: height }
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Send> Array2<T> {
+    /// Calls `f` on every row of the array in parallel, using rayon's thread pool.
+    pub fn par_rows_mut<F: Fn(&mut [T]) + Sync>(&mut self, f: F) {
+        let width = self.width as usize;
+        if width > 0 {
+            self.as_slice_mut().par_chunks_mut(width).for_each(|row| f(row));
+        }
+    }
 }
 
 impl<T> Array2<T> {
     /// Constructs an `Array2<T>` from `width` and `height` by repeatedly calling `f`.
+    ///
+    /// # Panics
+    /// Panics if the required allocation would exceed the maximum allocation size.
     pub fn from_fn<F: FnMut() -> T>(width: u32, height: u32, mut f: F) -> Array2<T> {
-        let allocation_required = mem::size_of::<T>() > 0 && width > 0 && height > 0;
-        let ptr = if allocation_required {
-            let count = width as usize * height as usize;
-            let ptr = unsafe { heap::allocate(count * mem::size_of::<T>(), mem::align_of::<T>()) } as *mut T;
-            if ptr.is_null() { ::std::process::exit(-9999); }
-            for offset in 0..count as isize {
-                unsafe { ptr::write(ptr.offset(offset), f()) }
+        Array2::build_or_exit(width, height, Init::With(&mut f))
+    }
+    
+    /// Allocates an array of `width` by `height` elements and initializes them as described by `init`.
+    /// Every constructor goes through this function.
+    #[inline]
+    fn build(width: u32, height: u32, init: Init<T>) -> Result<Array2<T>, BuildError> {
+        let count = width as usize * height as usize;
+        let ptr = try!(unsafe { Array2::<T>::resize_allocation(ptr::null_mut(), (0, 0), (width, height)) });
+        match init {
+            Init::Uninit => {}
+            // Zero-sized elements are written too, so that every element the array drops was constructed.
+            Init::With(f) => {
+                for offset in 0..count as isize {
+                    unsafe { ptr::write(ptr.offset(offset), f()) }
+                }
             }
-            unsafe { Unique::new(ptr) }
-        } else {
-            unsafe { Unique::new(heap::EMPTY as *mut T) }
+            Init::Copy(src) => {
+                assert_eq!(src.len(), count);
+                unsafe { ptr::copy_nonoverlapping(src.as_ptr(), ptr, count) }
+            }
+        }
+        Ok(Array2 { ptr: unsafe { Unique::new(ptr) }, width: width, height: height })
+    }
+    
+    /// Resizes the allocation at `ptr`, which holds `old` width by height elements, to hold `new` width by height
+    /// elements and returns the new pointer. The first elements are preserved as with `heap::reallocate`.
+    /// If `old` describes no allocated bytes, `ptr` is ignored and a new allocation is made.
+    /// Together with `build`, this is the only place where arrays are allocated.
+    ///
+    /// On error, the old allocation is left untouched.
+    unsafe fn resize_allocation(ptr: *mut T, old: (u32, u32), new: (u32, u32)) -> Result<*mut T, BuildError> {
+        let old_bytes = Array2::<T>::allocation_size(old.0, old.1).expect("existing arrays never exceed the maximum size");
+        let new_bytes = match Array2::<T>::allocation_size(new.0, new.1) {
+            Some(bytes) => bytes,
+            None => return Err(BuildError::Overflow)
         };
-        Array2 { ptr: ptr, width: width, height: height }
+        if new_bytes == 0 {
+            if old_bytes > 0 {
+                heap::deallocate(ptr as *mut u8, old_bytes, mem::align_of::<T>());
+            }
+            // Zero-sized elements and empty arrays need no allocation. The dangling pointer is
+            // suitably aligned, so slices over the array are still valid.
+            return Ok(mem::align_of::<T>() as *mut T);
+        }
+        let new_ptr = if old_bytes > 0 {
+            heap::reallocate(ptr as *mut u8, old_bytes, new_bytes, mem::align_of::<T>())
+        } else {
+            heap::allocate(new_bytes, mem::align_of::<T>())
+        } as *mut T;
+        if new_ptr.is_null() {
+            Err(BuildError::AllocFailed)
+        } else {
+            Ok(new_ptr)
+        }
+    }
+    
+    /// Calls `build` for constructors that cannot report errors. Panics if the allocation size
+    /// overflows and exits the process if the allocation fails.
+    #[inline]
+    fn build_or_exit(width: u32, height: u32, init: Init<T>) -> Array2<T> {
+        match Array2::build(width, height, init) {
+            Ok(array) => array,
+            Err(BuildError::Overflow) => panic!("Array2 capacity overflow: {}x{} elements", width, height),
+            Err(BuildError::AllocFailed) => ::std::process::exit(-9999)
+        }
+    }
+    
+    /// Allocates an array of `width * height` uninitialized elements. The elements can be written
+    /// in any order and the array turned into an `Array2<T>` with `assume_init` once all of them are set,
+    /// which avoids an initialization pass over the whole array.
+    ///
+    /// Dropping the returned array never drops any `T`, so elements written to it before it is dropped leak.
+    pub fn new_uninit(width: u32, height: u32) -> Array2<MaybeUninit<T>> {
+        Array2::build_or_exit(width, height, Init::Uninit)
+    }
+    
+    /// Constructs an `Array2<T>` from `width` and `height` by repeatedly calling `f`.
+    /// Returns `None` if the required allocation would exceed the maximum allocation size or cannot be
+    /// satisfied, which makes it suitable for dimensions from untrusted sources.
+    pub fn checked_from_fn<F: FnMut() -> T>(width: u32, height: u32, mut f: F) -> Option<Array2<T>> {
+        Array2::build(width, height, Init::With(&mut f)).ok()
     }
     
     /// Constructs an `Array2<T>` from `width` and `height` by repeatedly calling `f` and passing
     /// the x and y coordinates of each element to it.
     pub fn from_fn_with_points<F: FnMut(u32, u32) -> T>(width: u32, height: u32, mut f: F) -> Array2<T> {
-        let mut iter = (0..height).flat_map(|y| iter::repeat(y).zip((0..width)));
+        let mut iter = (0..height).flat_map(|y| (0..width).zip(iter::repeat(y)));
         Array2::from_fn(width, height, || {
             let (x, y) = iter.next().unwrap();
             f(x, y)
         })
     }
     
+    /// Constructs an `Array2<T>` from a vector of rows. Returns `None` if the rows differ in length.
+    /// An empty vector results in a 0x0 array.
+    pub fn from_nested_vec(rows: Vec<Vec<T>>) -> Option<Array2<T>> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let height = rows.len();
+        let dimensions_are_valid = width <= ::std::u32::MAX as usize && height <= ::std::u32::MAX as usize;
+        if !dimensions_are_valid || rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        let mut iter = rows.into_iter().flat_map(|row| row.into_iter());
+        Some(Array2::from_fn(width as u32, height as u32, || iter.next().unwrap()))
+    }
+    
+    /// Appends `rows` beneath the last row of the array, reallocating it to the new height.
+    /// Returns `Err` and leaves the array unchanged if a row does not contain exactly `width` elements,
+    /// if the new height would overflow `u32` or the maximum allocation size, or if the allocation fails.
+    pub fn extend_rows<I, R>(&mut self, rows: I) -> Result<(), ()>
+        where I: IntoIterator<Item = R>, R: IntoIterator<Item = T>
+    {
+        let width = self.width as usize;
+        let mut elements = Vec::new();
+        let mut added_rows = 0usize;
+        for row in rows {
+            let len = elements.len();
+            elements.extend(row.into_iter().take(width + 1));
+            if elements.len() - len != width {
+                return Err(());
+            }
+            added_rows += 1;
+        }
+        if added_rows > (::std::u32::MAX - self.height) as usize {
+            return Err(());
+        }
+        let new_height = self.height + added_rows as u32;
+        let old_count = self.as_slice().len();
+        if mem::size_of::<T>() > 0 && elements.len() > 0 {
+            let new_dimensions = (self.width, new_height);
+            let ptr = match unsafe { Array2::<T>::resize_allocation(*self.ptr, self.dimensions(), new_dimensions) } {
+                Ok(ptr) => ptr,
+                Err(_) => return Err(())
+            };
+            unsafe {
+                ptr::copy_nonoverlapping(elements.as_ptr(), ptr.offset(old_count as isize), elements.len());
+                self.ptr = Unique::new(ptr);
+            }
+        }
+        unsafe { elements.set_len(0); }
+        self.height = new_height;
+        Ok(())
+    }
+    
+    /// Changes the dimensions of the array to `new_width` and `new_height`. Elements inside both the old
+    /// and the new bounds keep their position, elements outside the new bounds are dropped and
+    /// newly exposed cells are filled by calling `f` with their x and y coordinates.
+    pub fn resize_with<F: FnMut(u32, u32) -> T>(&mut self, new_width: u32, new_height: u32, mut f: F) {
+        let (old_width, old_height) = (self.width, self.height);
+        let old = mem::replace(self, Array2::from_fn(0, 0, || unreachable!()));
+        let mut old_elements = old.into_iter();
+        let mut consumed = 0;
+        *self = Array2::from_fn_with_points(new_width, new_height, |x, y| {
+            if x < old_width && y < old_height {
+                let index = x as usize + y as usize * old_width as usize;
+                for _ in consumed..index {
+                    old_elements.next();
+                }
+                consumed = index + 1;
+                old_elements.next().unwrap()
+            } else {
+                f(x, y)
+            }
+        });
+    }
+    
+    /// Constructs an `Array2<T>` from a multi-line string by passing each character to `f`.
+    /// Each line becomes a row. Trailing empty lines are ignored. Returns `None` if the remaining lines
+    /// do not all have the same number of characters.
+    pub fn from_str_map<F: FnMut(char) -> T>(s: &str, mut f: F) -> Option<Array2<T>> {
+        let mut lines: Vec<&str> = s.lines().collect();
+        while lines.last().map_or(false, |line| line.is_empty()) {
+            lines.pop();
+        }
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        if lines.iter().any(|line| line.chars().count() != width) || width > ::std::u32::MAX as usize
+            || lines.len() > ::std::u32::MAX as usize
+        {
+            return None;
+        }
+        let mut chars = lines.iter().flat_map(|line| line.chars());
+        Some(Array2::from_fn(width as u32, lines.len() as u32, || f(chars.next().unwrap())))
+    }
+    
+    /// Replaces the elements of row `y` with the items of `iter`, dropping the previous elements.
+    /// Returns `Err` and leaves the row unchanged if `y` is out of bounds or `iter` does not yield
+    /// exactly `width` items.
+    pub fn overwrite_row<I: IntoIterator<Item = T>>(&mut self, y: u32, iter: I) -> Result<(), ()> {
+        let range = match self.row_range(y) {
+            Some(range) => range,
+            None => return Err(())
+        };
+        let elements: Vec<T> = iter.into_iter().take(self.width as usize + 1).collect();
+        if elements.len() != self.width as usize {
+            return Err(());
+        }
+        for (dst, src) in self.as_slice_mut()[range].iter_mut().zip(elements.into_iter()) {
+            *dst = src;
+        }
+        Ok(())
+    }
+    
     /// Returns a reference to the element at the given position, or `None` if the position is invalid.
     pub fn get(&self, x: u32, y: u32) -> Option<&T> {
         if x < self.width && y < self.height {
-            unsafe { self.ptr.offset(x as isize + y as isize * self.width as isize).as_ref() }
+            unsafe { Some(&*self.element_ptr(x, y)) }
         } else {
             None
         }
@@ -76,20 +558,84 @@ impl<T> Array2<T> {
     /// Returns a mutable reference to the element at the given position, or `None` if the position is invalid.
     pub fn get_mut(&mut self, x: u32, y: u32) -> Option<&mut T> {
         if x < self.width && y < self.height {
-            unsafe { self.ptr.offset(x as isize + y as isize * self.width as isize).as_mut() }
+            unsafe { Some(&mut *self.element_ptr(x, y)) }
         } else {
             None
         }
     }
 
+    /// Returns a reference to the element at the given position.
+    /// Behaves like indexing, but can be called in method position, e.g. inside closures.
+    ///
+    /// # Panics
+    /// Panics if the position is out of bounds.
+    pub fn at<P: Point2>(&self, point: P) -> &T {
+        unsafe { &*self.checked_element_ptr(point.x(), point.y()) }
+    }
+    
+    /// Returns a mutable reference to the element at the given position.
+    /// Behaves like indexing, but can be called in method position, e.g. inside closures.
+    ///
+    /// # Panics
+    /// Panics if the position is out of bounds.
+    pub fn at_mut<P: Point2>(&mut self, point: P) -> &mut T {
+        unsafe { &mut *self.checked_element_ptr(point.x(), point.y()) }
+    }
+
+    /// Returns a mutable reference to the element at `(x, y)` together with mutable references to its
+    /// orthogonal neighbors in the order up, right, down, left. Neighbors outside the array are `None`.
+    /// Returns `None` if the position is out of bounds.
+    pub fn split_cell_neighbors4(&mut self, x: u32, y: u32) -> Option<(&mut T, [Option<&mut T>; 4])> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let center = self.element_ptr(x, y);
+        let up = if y > 0 { Some(self.element_ptr(x, y - 1)) } else { None };
+        let right = if x + 1 < self.width { Some(self.element_ptr(x + 1, y)) } else { None };
+        let down = if y + 1 < self.height { Some(self.element_ptr(x, y + 1)) } else { None };
+        let left = if x > 0 { Some(self.element_ptr(x - 1, y)) } else { None };
+        // All five positions are distinct, so none of the references alias each other.
+        unsafe {
+            Some((&mut *center, [up.map(|p| &mut *p), right.map(|p| &mut *p), down.map(|p| &mut *p), left.map(|p| &mut *p)]))
+        }
+    }
+    
+    /// Returns how many of the up to eight cells surrounding `(x, y)` satisfy `p`.
+    /// Neighbors outside the array bounds are not counted.
+    pub fn count_neighbors8_where<P: Fn(&T) -> bool>(&self, x: u32, y: u32, p: P) -> usize {
+        let mut count = 0;
+        for dy in -1i64..2 {
+            for dx in -1i64..2 {
+                let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+                let is_neighbor = (dx != 0 || dy != 0) && nx >= 0 && ny >= 0;
+                if is_neighbor && nx < self.width as i64 && ny < self.height as i64 && p(self.at((nx as u32, ny as u32))) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+    
+    /// Writes `value` to the given position and returns the element previously stored there,
+    /// or returns `None` if the position is out of bounds.
+    pub fn replace<P: Point2>(&mut self, point: P, value: T) -> Option<T> {
+        self.get_mut(point.x(), point.y()).map(|e| mem::replace(e, value))
+    }
+    
     /// Returns an iterator over the elements of the array.
     pub fn iter(&self) -> Items<T> {
-        Items { ptr: *self.ptr, end: self.end(), marker: PhantomData }
+        Items { ptr: *self.ptr, end: self.end(), width: self.width, marker: PhantomData }
     }
     
     /// Returns a mutable iterator over the elements of the array.
     pub fn iter_mut(&mut self) -> ItemsMut<T> {
-        ItemsMut { ptr: *self.ptr, end: self.end(), marker: PhantomData }
+        ItemsMut { ptr: *self.ptr, end: self.end(), width: self.width, marker: PhantomData }
+    }
+    
+    /// Returns a mutable iterator over the elements of the array in row-major order, yielding the x and
+    /// y coordinates of each element along with it. Shorthand for `iter_mut().with_points()`.
+    pub fn pixels_mut(&mut self) -> WithPoints<ItemsMut<T>> {
+        self.iter_mut().with_points()
     }
     
     /// Returns an iterator over the rows of the array. Rows are represented as slice.
@@ -97,6 +643,25 @@ impl<T> Array2<T> {
         Rows { ptr: *self.ptr, end: self.end(), len: self.width as usize, marker: PhantomData }
     }
     
+    /// Returns an iterator over bands of `n` consecutive rows. Each band is a single slice of `n * width`
+    /// elements, except for the last band, which is shorter if `height` is not a multiple of `n`.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0.
+    pub fn row_bands(&self, n: u32) -> slice::Chunks<T> {
+        assert!(n > 0, "row_bands requires at least one row per band");
+        let band_len = cmp::max(n as usize * self.width as usize, 1);
+        self.as_slice().chunks(band_len)
+    }
+    
+    /// Returns a vector of slices over the rows of the array. The vector has `height` entries,
+    /// each of which has `width` elements. No elements are copied.
+    pub fn row_slices(&self) -> Vec<&[T]> {
+        let width = self.width as usize;
+        let slice = self.as_slice();
+        (0..self.height as usize).map(|y| &slice[y * width..(y + 1) * width]).collect()
+    }
+    
     /// Returns a mutable iterator over the rows of the array. Rows are represented as slice.
     pub fn rows_mut(&mut self) -> RowsMut<T> {
         RowsMut { ptr: *self.ptr, end: self.end(), len: self.width as usize, marker: PhantomData }
@@ -115,6 +680,46 @@ impl<T> Array2<T> {
         }
     }
     
+    /// Returns an iterator over the rows of a rectangular section of the array like `view`, or `None` if
+    /// the origin lies outside the array or the section has no width or height.
+    pub fn checked_view(&self, x: u32, y: u32, width: u32, height: u32) -> Option<View<T>> {
+        if x < self.width && y < self.height && width > 0 && height > 0 {
+            Some(self.view(x, y, width, height))
+        } else {
+            None
+        }
+    }
+    
+    /// Returns a mutable iterator over the rows of a rectangular section of the array like `view_mut`, or `None` if
+    /// the origin lies outside the array or the section has no width or height.
+    pub fn checked_view_mut(&mut self, x: u32, y: u32, width: u32, height: u32) -> Option<ViewMut<T>> {
+        if x < self.width && y < self.height && width > 0 && height > 0 {
+            Some(self.view_mut(x, y, width, height))
+        } else {
+            None
+        }
+    }
+    
+    /// Returns an iterator over the rows of the section of the array spanned by the column range `xs`
+    /// and the row range `ys`. Equivalent to `view` with the ranges' start and length.
+    /// Empty or reversed ranges result in an empty iterator.
+    pub fn rect(&self, xs: Range<u32>, ys: Range<u32>) -> View<T> {
+        self.view(xs.start, ys.start, xs.end.saturating_sub(xs.start), ys.end.saturating_sub(ys.start))
+    }
+    
+    /// Returns a mutable iterator over the rows of the section of the array spanned by the column range `xs`
+    /// and the row range `ys`. Equivalent to `view_mut` with the ranges' start and length.
+    /// Empty or reversed ranges result in an empty iterator.
+    pub fn rect_mut(&mut self, xs: Range<u32>, ys: Range<u32>) -> ViewMut<T> {
+        self.view_mut(xs.start, ys.start, xs.end.saturating_sub(xs.start), ys.end.saturating_sub(ys.start))
+    }
+    
+    /// Returns an iterator over the rows of a rectangular section of the array, where each row is paired
+    /// with its y coordinate in the array. Parts of the section that exceed the array bounds will be skipped.
+    pub fn view_rows_enumerate(&self, x: u32, y: u32, width: u32, height: u32) -> ViewRowsEnumerate<T> {
+        ViewRowsEnumerate { view: self.view(x, y, width, height), y: y }
+    }
+    
     /// Returns a mutable iterator over the rows of a rectangular section of the array.
     /// Parts of the section that exceed the array bounds will be skipped.
     pub fn view_mut(&mut self, x: u32, y: u32, width: u32, height: u32) -> ViewMut<T> {
@@ -128,100 +733,530 @@ impl<T> Array2<T> {
         }
     }
     
-    /// Returns the width of the array.
-    pub fn width(&self) -> u32 {
-        self.width
+    /// Returns an iterator over the perimeter of the array, yielding each cell together with its
+    /// coordinates. Starting at the top left, the top row is traversed left to right, then the right column
+    /// downwards, the bottom row right to left and finally the left column upwards.
+    /// Every cell is visited exactly once.
+    pub fn border(&self) -> Border<T> {
+        let len = if self.width == 0 || self.height == 0 {
+            0
+        } else if self.width == 1 || self.height == 1 {
+            self.width + self.height - 1
+        } else {
+            2 * self.width + 2 * self.height - 4
+        };
+        Border { array: self, index: 0, len: len }
     }
     
-    /// Returns the height of the array.
-    pub fn height(&self) -> u32 {
-        self.height
+    /// Returns an iterator over the elements of a rectangular section of the array in the order in which
+    /// they would appear, row by row, after rotating the section clockwise by `quarter_turns * 90` degrees.
+    /// The section is clipped to the array bounds as with `view`.
+    pub fn view_rotated(&self, x: u32, y: u32, width: u32, height: u32, quarter_turns: u8) -> ViewRotated<T> {
+        let (x, y, width, height) = self.clamped_rect(x, y, width, height).unwrap_or((0, 0, 0, 0));
+        ViewRotated {
+            array: self,
+            x: x,
+            y: y,
+            width: width,
+            height: height,
+            quarter_turns: quarter_turns % 4,
+            index: 0,
+            len: width as usize * height as usize
+        }
     }
     
-    /// Returns a slice over all elements in the array. 
-    pub fn as_slice(&self) -> &[T] {
-        unsafe { slice::from_raw_parts(*self.ptr, self.width as usize * self.height as usize) }
+    /// Returns an iterator over all top-left to bottom-right diagonals of the array, each collected into a vector.
+    /// There are `width + height - 1` diagonals, starting with the one at the bottom left corner
+    /// and ending with the one at the top right corner. An array without elements has no diagonals.
+    pub fn diagonals(&self) -> Diagonals<T> {
+        let len = if self.width == 0 || self.height == 0 {
+            0
+        } else {
+            self.width as usize + self.height as usize - 1
+        };
+        Diagonals { array: self, index: 0, len: len }
     }
     
-    /// Returns a mutable slice over all elements in the array.
-    pub fn as_slice_mut(&mut self) -> &mut [T] {
-        unsafe { slice::from_raw_parts_mut(*self.ptr, self.width as usize * self.height as usize) }
+    /// Returns true if `(x, y)` lies within the array on its outer edge, i.e. it is one of the cells yielded by `border`.
+    pub fn is_border(&self, x: u32, y: u32) -> bool {
+        x < self.width && y < self.height &&
+        (x == 0 || y == 0 || x == self.width - 1 || y == self.height - 1)
     }
-
-    #[inline]
-    fn end(&self) -> *mut T {
-        if mem::size_of::<T>() > 0 {
-            unsafe { self.ptr.offset(self.width as isize * self.height as isize) }
+    
+    /// Returns true if `(x, y)` is one of the corners of the array.
+    pub fn is_corner(&self, x: u32, y: u32) -> bool {
+        x < self.width && y < self.height &&
+        (x == 0 || x == self.width - 1) && (y == 0 || y == self.height - 1)
+    }
+    
+    /// Transposes a square array in place by swapping the elements at `(x, y)` and `(y, x)`.
+    ///
+    /// # Panics
+    /// Panics if the width and height of the array differ.
+    pub fn transpose_in_place(&mut self) {
+        assert!(self.width == self.height, "transpose_in_place requires a square array, but the dimensions are {}x{}",
+            self.width, self.height);
+        let size = self.width as isize;
+        for y in 0..size {
+            for x in 0..y {
+                unsafe { ptr::swap(self.ptr.offset(x + y * size), self.ptr.offset(y + x * size)) }
+            }
+        }
+    }
+    
+    /// Returns the range of indices that row `y` occupies in `as_slice`, or `None` if `y` is out of bounds.
+    pub fn row_range(&self, y: u32) -> Option<Range<usize>> {
+        if y < self.height {
+            let start = y as usize * self.width as usize;
+            Some(start..start + self.width as usize)
         } else {
-            (*self.ptr as usize + self.width as usize * self.height as usize) as *mut T
+            None
         }
     }
     
-    #[inline]
-    fn view_components(&self, x: u32, y: u32, mut width: u32, mut height: u32) -> (*mut T, *mut T, usize, isize) {
-        let input_is_valid = x < self.width && y < self.height && width > 0 && height > 0;
-        let (ptr, end) = if input_is_valid {
-            width = cmp::min(width, self.width - x);
-            height = cmp::min(height, self.height - y);
-            if mem::size_of::<T>() > 0 {
-                let ptr_offset = x as isize + y as isize * self.width as isize;
-                let ptr = unsafe { self.ptr.offset(ptr_offset) };
-                let end_offset = height as isize * self.width as isize;
-                let end = unsafe { ptr.offset(end_offset) };
-                (ptr, end)
-            } else {
-                (*self.ptr, (*self.ptr as usize + height as usize) as *mut T)
+    /// Returns a pointer to the first element of row `y`.
+    ///
+    /// # Safety
+    /// No bounds checking is performed. `y` must be less than the height of the array
+    /// and the pointer must not be used to read past the end of the row's `width` elements.
+    pub unsafe fn row_ptr(&self, y: u32) -> *const T {
+        self.ptr.offset(y as isize * self.width as isize)
+    }
+    
+    /// Returns an iterator over the cells on the line from `(x0, y0)` to `(x1, y1)`, both inclusive,
+    /// as determined by Bresenham's line algorithm. Each cell is yielded together with its coordinates.
+    /// The iterator is empty if either endpoint is out of bounds.
+    pub fn line(&self, x0: u32, y0: u32, x1: u32, y1: u32) -> Line<T> {
+        let endpoints_are_valid = x0 < self.width && x1 < self.width && y0 < self.height && y1 < self.height;
+        let (x0, y0, x1, y1) = (x0 as i64, y0 as i64, x1 as i64, y1 as i64);
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        Line {
+            array: self,
+            x: x0,
+            y: y0,
+            x1: x1,
+            y1: y1,
+            dx: dx,
+            dy: dy,
+            sx: if x0 < x1 { 1 } else { -1 },
+            sy: if y0 < y1 { 1 } else { -1 },
+            err: dx + dy,
+            done: !endpoints_are_valid
+        }
+    }
+    
+    /// Swaps the contents and dimensions of two arrays in constant time, without moving any elements.
+    pub fn swap_with(&mut self, other: &mut Array2<T>) {
+        mem::swap(self, other);
+    }
+    
+    /// Cyclically moves the rows of the array down by `n`. Rows that move past the bottom edge
+    /// reappear at the top. `rotate_rows(height)` leaves the array unchanged.
+    pub fn rotate_rows(&mut self, n: u32) {
+        if self.height > 0 {
+            let k = (n % self.height) as usize * self.width as usize;
+            rotate_right(self.as_slice_mut(), k);
+        }
+    }
+    
+    /// Reorders the rows of the array according to `compare`, which is called with pairs of rows.
+    /// The sort is stable. Only a permutation of row indices is allocated, the rows are then swapped in place.
+    pub fn sort_rows_by<F: FnMut(&[T], &[T]) -> Ordering>(&mut self, mut compare: F) {
+        let mut order: Vec<usize> = (0..self.height as usize).collect();
+        {
+            let rows = self.row_slices();
+            order.sort_by(|&a, &b| compare(rows[a], rows[b]));
+        }
+        // `order[y]` is the index of the row that belongs at `y`. Each cycle of the permutation
+        // is applied by swapping rows along it, marking visited positions as already in place.
+        let width = self.width as usize;
+        let slice = self.as_slice_mut();
+        for start in 0..order.len() {
+            let mut y = start;
+            loop {
+                let source = order[y];
+                order[y] = y;
+                if source == start || source == y {
+                    break;
+                }
+                for x in 0..width {
+                    slice.swap(y * width + x, source * width + x);
+                }
+                y = source;
             }
-        } else {
-            (*self.ptr, *self.ptr)
-        };
-        (ptr, end, width as usize, self.width as isize)
+        }
     }
-}
-
-impl<T> Drop for Array2<T> {
-    fn drop(&mut self) {
-        let deallocation_required = *self.ptr != heap::EMPTY as *mut T;
-        if deallocation_required {
-            for e in self.iter() { 
-                unsafe { ptr::read(e); }
+    
+    /// Cyclically moves the columns of the array right by `n`. Columns that move past the right edge
+    /// reappear on the left. `rotate_cols(width)` leaves the array unchanged.
+    pub fn rotate_cols(&mut self, n: u32) {
+        if self.width > 0 {
+            let k = (n % self.width) as usize;
+            for row in self.rows_mut() {
+                rotate_right(row, k);
             }
-            let bytes = self.width as usize * self.height as usize * mem::size_of::<T>();
-            unsafe { heap::deallocate(*self.ptr as *mut u8, bytes, mem::align_of::<T>()); }
         }
     }
-}
     
-/// An iterator over the elements of the array.
-pub struct Items<'a, T: 'a> {
-    ptr: *const T,
-    end: *const T,
-    marker: PhantomData<&'a T>
-}
-
-impl<'a, T> Iterator for Items<'a, T> {
-    type Item = &'a T;
+    /// Returns the element for which `f` returns the smallest key together with its position,
+    /// or `None` if the array is empty. On ties, the first element in row-major order is returned.
+    pub fn min_by_key<K: Ord, F: FnMut(&T) -> K>(&self, f: F) -> Option<(u32, u32, &T)> {
+        self.extremum_by_key(f, Ordering::Less)
+    }
     
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.ptr < self.end {
-            let r = unsafe { &*self.ptr };
-            if mem::size_of::<T>() > 0 {
-                self.ptr = unsafe { self.ptr.offset(1) };
-            } else {
-                self.ptr = (self.ptr as usize + 1) as *const T;
-            }
-            Some(r)
+    /// Returns the element for which `f` returns the largest key together with its position,
+    /// or `None` if the array is empty. On ties, the first element in row-major order is returned.
+    pub fn max_by_key<K: Ord, F: FnMut(&T) -> K>(&self, f: F) -> Option<(u32, u32, &T)> {
+        self.extremum_by_key(f, Ordering::Greater)
+    }
+    
+    /// Returns the position and dimensions `(x, y, width, height)` of the given rectangle after clipping it
+    /// to the array bounds, i.e. the section that `view` and `view_mut` iterate over.
+    /// Returns `None` if the origin lies outside the array.
+    pub fn clamped_rect(&self, x: u32, y: u32, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+        if x < self.width && y < self.height {
+            Some((x, y, cmp::min(width, self.width - x), cmp::min(height, self.height - y)))
         } else {
             None
         }
     }
-}
-
-/// A mutable iterator over the elements of the array.
-pub struct ItemsMut<'a, T: 'a> {
-    ptr: *mut T,
-    end: *mut T,
-    marker: PhantomData<&'a mut T>
+    
+    /// Partitions the array into blocks of `bw` by `bh` elements and returns an array with one element
+    /// per block, obtained by passing references to the block's elements in row-major order to `reduce`.
+    /// Blocks at the right and bottom edges are smaller if the dimensions are not multiples of the block size.
+    ///
+    /// # Panics
+    /// Panics if `bw` or `bh` is 0.
+    pub fn downsample<F: FnMut(&[&T]) -> T>(&self, bw: u32, bh: u32, mut reduce: F) -> Array2<T> {
+        assert!(bw > 0 && bh > 0, "downsample requires a block size of at least 1x1");
+        let width = self.width / bw + if self.width % bw > 0 { 1 } else { 0 };
+        let height = self.height / bh + if self.height % bh > 0 { 1 } else { 0 };
+        let mut block = Vec::with_capacity(bw as usize * bh as usize);
+        Array2::from_fn_with_points(width, height, |x, y| {
+            block.clear();
+            for row in self.view(x * bw, y * bh, bw, bh) {
+                block.extend(row.iter());
+            }
+            reduce(&block)
+        })
+    }
+    
+    /// Renders the array as a string with one line per row, passing each element to `f` to obtain its character.
+    /// The last line is not terminated by a newline. An array without elements renders as an empty string.
+    pub fn to_string_map<F: FnMut(&T) -> char>(&self, mut f: F) -> String {
+        let mut s = String::with_capacity(self.as_slice().len() + self.height as usize);
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                s.push('\n');
+            }
+            s.extend(row.iter().map(&mut f));
+        }
+        s
+    }
+    
+    /// Consumes the array and returns its transpose, in which the element at `(x, y)` is moved to `(y, x)`.
+    /// No elements are cloned.
+    pub fn into_transposed(self) -> Array2<T> {
+        let transposed = Array2::from_fn_with_points(self.height, self.width, |x, y| {
+            unsafe { ptr::read(self.element_ptr(y, x)) }
+        });
+        // Every element has been moved out, so only the allocation is left to free.
+        let bytes = self.allocated_bytes();
+        if bytes > 0 {
+            unsafe { heap::deallocate(*self.ptr as *mut u8, bytes, mem::align_of::<T>()); }
+        }
+        mem::forget(self);
+        transposed
+    }
+    
+    /// Combines `src` into the array with its top left corner placed at `(dest_x, dest_y)`, calling `f`
+    /// with each overlapped element and the corresponding element of `src`. Parts of `src` that exceed
+    /// the array bounds are skipped, as with `view_mut`.
+    pub fn blend_from<F: FnMut(&mut T, &T)>(&mut self, dest_x: u32, dest_y: u32, src: &Array2<T>, mut f: F) {
+        for (dest_row, src_row) in self.view_mut(dest_x, dest_y, src.width, src.height).zip(src.rows()) {
+            for (dest, src) in dest_row.iter_mut().zip(src_row.iter()) {
+                f(dest, src);
+            }
+        }
+    }
+    
+    /// Returns `Ok` with the array if its width and height are equal, otherwise `Err` with the array.
+    pub fn try_into_square(self) -> Result<Array2<T>, Array2<T>> {
+        if self.width == self.height {
+            Ok(self)
+        } else {
+            Err(self)
+        }
+    }
+    
+    /// Returns an array whose rows are obtained by passing each row of this array to `f`. The height is unchanged
+    /// and the width is the length of the produced rows. Returns `None` if the produced rows differ in length.
+    pub fn map_rows<U, F: FnMut(&[T]) -> Vec<U>>(&self, f: F) -> Option<Array2<U>> {
+        Array2::from_nested_vec(self.row_slices().into_iter().map(f).collect())
+    }
+    
+    /// Returns an array of the same dimensions holding the result of applying `p` to each element.
+    pub fn mask<P: FnMut(&T) -> bool>(&self, mut p: P) -> Array2<bool> {
+        let mut iter = self.iter();
+        Array2::from_fn(self.width, self.height, || p(iter.next().unwrap()))
+    }
+    
+    /// Returns the width of the array.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    
+    /// Returns the height of the array.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+    
+    /// Returns the width and height of the array.
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+    
+    /// Returns the number of elements the array can hold. Arrays are allocated with exactly
+    /// `width * height` elements and never keep spare capacity, so this is always `width * height`.
+    /// Zero-sized elements need no allocation, but the array still holds `width * height` of them.
+    pub fn capacity(&self) -> usize {
+        self.as_slice().len()
+    }
+    
+    /// Returns a slice over all elements in the array. 
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(*self.ptr, self.width as usize * self.height as usize) }
+    }
+    
+    /// Returns a slice over all elements in the array together with the row stride, i.e. the distance
+    /// in elements between the starts of two consecutive rows. Rows are currently stored without padding,
+    /// so the stride equals the width, but callers should rely on the returned stride instead.
+    pub fn as_strided(&self) -> (&[T], usize) {
+        (self.as_slice(), self.width as usize)
+    }
+    
+    /// Returns a mutable slice over all elements in the array.
+    pub fn as_slice_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(*self.ptr, self.width as usize * self.height as usize) }
+    }
+
+    fn extremum_by_key<'a, K: Ord, F: FnMut(&'a T) -> K>(&'a self, mut f: F, ordering: Ordering) -> Option<(u32, u32, &'a T)> {
+        let mut best: Option<(usize, K)> = None;
+        for (i, e) in self.iter().enumerate() {
+            let key = f(e);
+            let is_better = match best {
+                Some((_, ref best_key)) => key.cmp(best_key) == ordering,
+                None => true
+            };
+            if is_better {
+                best = Some((i, key));
+            }
+        }
+        best.map(|(i, _)| {
+            let width = self.width as usize;
+            let (x, y) = ((i % width) as u32, (i / width) as u32);
+            (x, y, &self.as_slice()[i])
+        })
+    }
+    
+    /// Returns the size in bytes of the array's allocation, as passed to the allocator by `build`.
+    /// Arrays that need no allocation report 0.
+    fn allocated_bytes(&self) -> usize {
+        Array2::<T>::allocation_size(self.width, self.height).expect("existing arrays never exceed the maximum size")
+    }
+    
+    /// Returns the number of bytes needed to store `width * height` elements,
+    /// or `None` if it overflows `usize` or exceeds `isize::MAX`.
+    fn allocation_size(width: u32, height: u32) -> Option<usize> {
+        (width as usize).checked_mul(height as usize)
+            .and_then(|count| count.checked_mul(mem::size_of::<T>()))
+            .and_then(|bytes| if bytes <= ::std::isize::MAX as usize { Some(bytes) } else { None })
+    }
+    
+    /// Returns a pointer to the element at the given position, which must be in bounds.
+    /// For zero-sized types no pointer arithmetic is performed, every element lives at `ptr`.
+    #[inline]
+    fn element_ptr(&self, x: u32, y: u32) -> *mut T {
+        if mem::size_of::<T>() > 0 {
+            unsafe { self.ptr.offset(x as isize + y as isize * self.width as isize) }
+        } else {
+            *self.ptr
+        }
+    }
+    
+    #[inline]
+    fn checked_element_ptr(&self, x: u32, y: u32) -> *mut T {
+        if x < self.width && y < self.height {
+            self.element_ptr(x, y)
+        } else {
+            panic!("Array2 index out of bounds: the position is ({}, {}) but the dimensions are {}x{}",
+                x, y, self.width, self.height)
+        }
+    }
+    
+    #[inline]
+    fn end(&self) -> *mut T {
+        if mem::size_of::<T>() > 0 {
+            unsafe { self.ptr.offset(self.width as isize * self.height as isize) }
+        } else {
+            (*self.ptr as usize + self.width as usize * self.height as usize) as *mut T
+        }
+    }
+    
+    #[inline]
+    fn view_components(&self, x: u32, y: u32, width: u32, height: u32) -> (*mut T, *mut T, usize, isize) {
+        match self.clamped_rect(x, y, width, height) {
+            Some((x, y, width, height)) if width > 0 && height > 0 => {
+                let (ptr, end) = if mem::size_of::<T>() > 0 {
+                    let ptr_offset = x as isize + y as isize * self.width as isize;
+                    let ptr = unsafe { self.ptr.offset(ptr_offset) };
+                    // `end` points one past the last element of the last row, which always lies within the allocation.
+                    let end_offset = (height - 1) as isize * self.width as isize + width as isize;
+                    let end = unsafe { ptr.offset(end_offset) };
+                    (ptr, end)
+                } else {
+                    (*self.ptr, (*self.ptr as usize + height as usize) as *mut T)
+                };
+                (ptr, end, width as usize, self.width as isize)
+            }
+            _ => (*self.ptr, *self.ptr, 0, self.width as isize)
+        }
+    }
+}
+
+impl<T> Drop for Array2<T> {
+    fn drop(&mut self) {
+        for e in self.iter() { 
+            unsafe { ptr::read(e); }
+        }
+        // Must match the size and alignment passed to `heap::allocate` when the array was constructed.
+        let bytes = self.allocated_bytes();
+        if bytes > 0 {
+            unsafe { heap::deallocate(*self.ptr as *mut u8, bytes, mem::align_of::<T>()); }
+        }
+    }
+}
+
+impl<T> IntoIterator for Array2<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+    
+    fn into_iter(self) -> IntoIter<T> {
+        let iter = IntoIter {
+            buf: unsafe { Unique::new(*self.ptr) },
+            bytes: self.allocated_bytes(),
+            ptr: *self.ptr,
+            end: self.end()
+        };
+        mem::forget(self);
+        iter
+    }
+}
+    
+/// An iterator that moves the elements out of the array.
+pub struct IntoIter<T> {
+    buf: Unique<T>,
+    bytes: usize,
+    ptr: *const T,
+    end: *const T
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ptr < self.end {
+            let e = unsafe { ptr::read(self.ptr) };
+            if mem::size_of::<T>() > 0 {
+                self.ptr = unsafe { self.ptr.offset(1) };
+            } else {
+                self.ptr = (self.ptr as usize + 1) as *const T;
+            }
+            Some(e)
+        } else {
+            None
+        }
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = if mem::size_of::<T>() > 0 {
+            (self.end as usize - self.ptr as usize) / mem::size_of::<T>()
+        } else {
+            self.end as usize - self.ptr as usize
+        };
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+// The iterator owns its elements and buffer just like `Array2<T>`, the raw cursor pointers
+// only point into that buffer.
+unsafe impl<T: Send> Send for IntoIter<T> {}
+unsafe impl<T: Sync> Sync for IntoIter<T> {}
+
+impl<T> Drop for IntoIter<T> {
+    fn drop(&mut self) {
+        for _ in self.by_ref() {}
+        if self.bytes > 0 {
+            unsafe { heap::deallocate(*self.buf as *mut u8, self.bytes, mem::align_of::<T>()); }
+        }
+    }
+}
+
+/// An iterator over the elements of the array.
+pub struct Items<'a, T: 'a> {
+    ptr: *const T,
+    end: *const T,
+    width: u32,
+    marker: PhantomData<&'a T>
+}
+
+impl<'a, T> Items<'a, T> {
+    /// Turns the iterator into one that also yields the x and y coordinates of each element.
+    pub fn with_points(self) -> WithPoints<Items<'a, T>> {
+        let width = self.width as usize;
+        WithPoints { iter: self, index: 0, width: width }
+    }
+}
+
+impl<'a, T> Iterator for Items<'a, T> {
+    type Item = &'a T;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ptr < self.end {
+            let r = unsafe { &*self.ptr };
+            if mem::size_of::<T>() > 0 {
+                self.ptr = unsafe { self.ptr.offset(1) };
+            } else {
+                self.ptr = (self.ptr as usize + 1) as *const T;
+            }
+            Some(r)
+        } else {
+            None
+        }
+    }
+    
+    fn count(self) -> usize {
+        rows_len(self.ptr, self.end, 1)
+    }
+}
+
+/// A mutable iterator over the elements of the array.
+pub struct ItemsMut<'a, T: 'a> {
+    ptr: *mut T,
+    end: *mut T,
+    width: u32,
+    marker: PhantomData<&'a mut T>
+}
+
+impl<'a, T> ItemsMut<'a, T> {
+    /// Turns the iterator into one that also yields the x and y coordinates of each element.
+    pub fn with_points(self) -> WithPoints<ItemsMut<'a, T>> {
+        let width = self.width as usize;
+        WithPoints { iter: self, index: 0, width: width }
+    }
 }
 
 impl<'a, T> Iterator for ItemsMut<'a, T> {
@@ -240,6 +1275,49 @@ impl<'a, T> Iterator for ItemsMut<'a, T> {
             None
         }
     }
+    
+    fn count(self) -> usize {
+        rows_len(self.ptr, self.end, 1)
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ItemsMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // `ptr` and `end` only ever move towards each other, so no element is yielded twice.
+        if self.ptr < self.end {
+            if mem::size_of::<T>() > 0 {
+                self.end = unsafe { self.end.offset(-1) };
+            } else {
+                self.end = (self.end as usize - 1) as *mut T;
+            }
+            Some(unsafe { &mut *self.end })
+        } else {
+            None
+        }
+    }
+}
+
+/// An iterator that yields the x and y coordinates of each element along with the element.
+pub struct WithPoints<I> {
+    iter: I,
+    index: usize,
+    width: usize
+}
+
+impl<I: Iterator> Iterator for WithPoints<I> {
+    type Item = (u32, u32, I::Item);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|e| {
+            let (x, y) = ((self.index % self.width) as u32, (self.index / self.width) as u32);
+            self.index += 1;
+            (x, y, e)
+        })
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
 }
 
 /// An iterator over the rows of the array.
@@ -266,6 +1344,24 @@ impl<'a, T> Iterator for Rows<'a, T> {
             None
         }
     }
+    
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= rows_len(self.ptr, self.end, self.len) {
+            self.ptr = self.end;
+            return None;
+        }
+        let skip = n * self.len;
+        if mem::size_of::<T>() > 0 {
+            self.ptr = unsafe { self.ptr.offset(skip as isize) };
+        } else {
+            self.ptr = (self.ptr as usize + skip) as *const T;
+        }
+        self.next()
+    }
+    
+    fn count(self) -> usize {
+        rows_len(self.ptr, self.end, self.len)
+    }
 }
 
 /// A mutable iterator over the rows of the array.
@@ -292,6 +1388,24 @@ impl<'a, T> Iterator for RowsMut<'a, T> {
             None
         }
     }
+    
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= rows_len(self.ptr, self.end, self.len) {
+            self.ptr = self.end;
+            return None;
+        }
+        let skip = n * self.len;
+        if mem::size_of::<T>() > 0 {
+            self.ptr = unsafe { self.ptr.offset(skip as isize) };
+        } else {
+            self.ptr = (self.ptr as usize + skip) as *mut T;
+        }
+        self.next()
+    }
+    
+    fn count(self) -> usize {
+        rows_len(self.ptr, self.end, self.len)
+    }
 }
 
 
@@ -304,6 +1418,20 @@ pub struct View<'a, T: 'a> {
     marker: PhantomData<&'a T>
 }
 
+// Implemented by hand because deriving would require `T: Clone`. `ViewMut` must not be cloneable,
+// as two clones would hand out mutable references to the same rows.
+impl<'a, T> Clone for View<'a, T> {
+    fn clone(&self) -> View<'a, T> {
+        View {
+            ptr: self.ptr,
+            end: self.end,
+            slice_len: self.slice_len,
+            array_width: self.array_width,
+            marker: PhantomData
+        }
+    }
+}
+
 impl<'a, T> Iterator for View<'a, T> {
     type Item = &'a [T];
     
@@ -311,7 +1439,14 @@ impl<'a, T> Iterator for View<'a, T> {
         if self.ptr < self.end {
             let slice = unsafe { slice::from_raw_parts(self.ptr, self.slice_len) };
             if mem::size_of::<T>() > 0 {
-                self.ptr = unsafe { self.ptr.offset(self.array_width) };
+                // Stepping past the last row could leave the allocation, so the view is exhausted
+                // by moving `ptr` to `end` instead.
+                let row_end = unsafe { self.ptr.offset(self.slice_len as isize) };
+                self.ptr = if row_end < self.end {
+                    unsafe { self.ptr.offset(self.array_width) }
+                } else {
+                    self.end
+                };
             } else {
                 self.ptr = (self.ptr as usize + 1) as *const T;
             }
@@ -320,8 +1455,61 @@ impl<'a, T> Iterator for View<'a, T> {
             None
         }
     }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = view_len(self.ptr, self.end, self.slice_len, self.array_width);
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for View<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.ptr < self.end {
+            if mem::size_of::<T>() > 0 {
+                let row = unsafe { self.end.offset(-(self.slice_len as isize)) };
+                // As in `next`, the end of the row before the first one may lie outside the allocation.
+                self.end = if row > self.ptr {
+                    unsafe { self.end.offset(-self.array_width) }
+                } else {
+                    self.ptr
+                };
+                Some(unsafe { slice::from_raw_parts(row, self.slice_len) })
+            } else {
+                self.end = (self.end as usize - 1) as *const T;
+                Some(unsafe { slice::from_raw_parts(self.end, self.slice_len) })
+            }
+        } else {
+            None
+        }
+    }
 }
 
+impl<'a, T> ExactSizeIterator for View<'a, T> {}
+
+/// An iterator over the rows of a rectangular section of the array and their y coordinates.
+pub struct ViewRowsEnumerate<'a, T: 'a> {
+    view: View<'a, T>,
+    y: u32
+}
+
+impl<'a, T> Iterator for ViewRowsEnumerate<'a, T> {
+    type Item = (u32, &'a [T]);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        self.view.next().map(|row| {
+            let y = self.y;
+            self.y += 1;
+            (y, row)
+        })
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.view.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for ViewRowsEnumerate<'a, T> {}
+
 /// A mutable iterator over the rows of a rectangular section of the array.
 pub struct ViewMut<'a, T: 'a> {
     ptr: *mut T,
@@ -338,7 +1526,14 @@ impl<'a, T> Iterator for ViewMut<'a, T> {
         if self.ptr < self.end { 
             let slice = unsafe { slice::from_raw_parts_mut(self.ptr, self.slice_len) };
             if mem::size_of::<T>() > 0 {
-                self.ptr = unsafe { self.ptr.offset(self.array_width) };
+                // Stepping past the last row could leave the allocation, so the view is exhausted
+                // by moving `ptr` to `end` instead.
+                let row_end = unsafe { self.ptr.offset(self.slice_len as isize) };
+                self.ptr = if row_end < self.end {
+                    unsafe { self.ptr.offset(self.array_width) }
+                } else {
+                    self.end
+                };
             } else {
                 self.ptr = (self.ptr as usize + 1) as *mut T;
             }
@@ -347,45 +1542,276 @@ impl<'a, T> Iterator for ViewMut<'a, T> {
             None
         }
     }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = view_len(self.ptr, self.end, self.slice_len, self.array_width);
+        (len, Some(len))
+    }
 }
 
-pub trait Point2 {
-    fn x(&self) -> u32;
-    fn y(&self) -> u32;
+impl<'a, T> DoubleEndedIterator for ViewMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.ptr < self.end {
+            if mem::size_of::<T>() > 0 {
+                let row = unsafe { self.end.offset(-(self.slice_len as isize)) };
+                // As in `next`, the end of the row before the first one may lie outside the allocation.
+                self.end = if row > self.ptr {
+                    unsafe { self.end.offset(-self.array_width) }
+                } else {
+                    self.ptr
+                };
+                Some(unsafe { slice::from_raw_parts_mut(row, self.slice_len) })
+            } else {
+                self.end = (self.end as usize - 1) as *mut T;
+                Some(unsafe { slice::from_raw_parts_mut(self.end, self.slice_len) })
+            }
+        } else {
+            None
+        }
+    }
 }
 
-impl Point2 for (u32, u32) {
-    fn x(&self) -> u32 { self.0 }
-    fn y(&self) -> u32 { self.1 }
-}
+impl<'a, T> ExactSizeIterator for ViewMut<'a, T> {}
 
-impl Point2 for [u32; 2] {
-    fn x(&self) -> u32 { self[0] }
-    fn y(&self) -> u32 { self[1] }
+/// Rotates `slice` right by `k` elements in place by reversing it in parts.
+fn rotate_right<T>(slice: &mut [T], k: usize) {
+    let len = slice.len();
+    slice.reverse();
+    slice[..k].reverse();
+    slice[k..len].reverse();
+}
+
+/// Returns the number of rows left in a view, where `ptr` is the start of the first row and `end` is one past
+/// the last element of the last row. For zero-sized types, `ptr` and `end` are row counters.
+#[inline]
+fn view_len<T>(ptr: *const T, end: *const T, slice_len: usize, array_width: isize) -> usize {
+    if ptr >= end {
+        0
+    } else if mem::size_of::<T>() > 0 {
+        let elements = (end as usize - ptr as usize) / mem::size_of::<T>();
+        (elements - slice_len) / array_width as usize + 1
+    } else {
+        end as usize - ptr as usize
+    }
+}
+
+/// The number of runs of `len` elements left between `ptr` and `end`, i.e. rows for `len == width`
+/// or single elements for `len == 1`.
+fn rows_len<T>(ptr: *const T, end: *const T, len: usize) -> usize {
+    if ptr >= end {
+        0
+    } else if mem::size_of::<T>() > 0 {
+        (end as usize - ptr as usize) / mem::size_of::<T>() / len
+    } else {
+        (end as usize - ptr as usize) / len
+    }
+}
+
+/// An iterator over the perimeter of the array.
+pub struct Border<'a, T: 'a> {
+    array: &'a Array2<T>,
+    index: u32,
+    len: u32
+}
+
+impl<'a, T> Iterator for Border<'a, T> {
+    type Item = (u32, u32, &'a T);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index < self.len {
+            let (width, height) = (self.array.width, self.array.height);
+            let mut i = self.index;
+            self.index += 1;
+            let (x, y) = if i < width {
+                (i, 0)
+            } else {
+                i -= width;
+                if i < height - 1 {
+                    (width - 1, i + 1)
+                } else {
+                    i -= height - 1;
+                    if i < width - 1 {
+                        (width - 2 - i, height - 1)
+                    } else {
+                        i -= width - 1;
+                        (0, height - 2 - i)
+                    }
+                }
+            };
+            Some((x, y, self.array.at((x, y))))
+        } else {
+            None
+        }
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.len - self.index) as usize;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Border<'a, T> {}
+
+/// An iterator over the top-left to bottom-right diagonals of the array.
+pub struct Diagonals<'a, T: 'a> {
+    array: &'a Array2<T>,
+    index: usize,
+    len: usize
+}
+
+impl<'a, T> Iterator for Diagonals<'a, T> {
+    type Item = Vec<&'a T>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index < self.len {
+            let (width, height) = (self.array.width as usize, self.array.height as usize);
+            let (x, y) = if self.index < height {
+                (0, height - 1 - self.index)
+            } else {
+                (self.index - height + 1, 0)
+            };
+            self.index += 1;
+            let len = cmp::min(width - x, height - y);
+            Some((0..len).map(|i| self.array.at(((x + i) as u32, (y + i) as u32))).collect())
+        } else {
+            None
+        }
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Diagonals<'a, T> {}
+
+/// An iterator over the elements of a rectangular section of the array in rotated order.
+pub struct ViewRotated<'a, T: 'a> {
+    array: &'a Array2<T>,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    quarter_turns: u8,
+    index: usize,
+    len: usize
+}
+
+impl<'a, T> Iterator for ViewRotated<'a, T> {
+    type Item = &'a T;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index < self.len {
+            let (w, h) = (self.width as usize, self.height as usize);
+            // Position within the rotated section, whose width is `h` after an odd number of turns.
+            let rotated_width = if self.quarter_turns % 2 == 0 { w } else { h };
+            let (rx, ry) = (self.index % rotated_width, self.index / rotated_width);
+            self.index += 1;
+            let (sx, sy) = match self.quarter_turns {
+                0 => (rx, ry),
+                1 => (ry, h - 1 - rx),
+                2 => (w - 1 - rx, h - 1 - ry),
+                _ => (w - 1 - ry, rx)
+            };
+            Some(self.array.at((self.x + sx as u32, self.y + sy as u32)))
+        } else {
+            None
+        }
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for ViewRotated<'a, T> {}
+
+/// An iterator over the cells on a line between two cells of the array.
+pub struct Line<'a, T: 'a> {
+    array: &'a Array2<T>,
+    x: i64,
+    y: i64,
+    x1: i64,
+    y1: i64,
+    dx: i64,
+    dy: i64,
+    sx: i64,
+    sy: i64,
+    err: i64,
+    done: bool
+}
+
+impl<'a, T> Iterator for Line<'a, T> {
+    type Item = (u32, u32, &'a T);
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let (x, y) = (self.x as u32, self.y as u32);
+        if self.x == self.x1 && self.y == self.y1 {
+            self.done = true;
+        } else {
+            let e2 = 2 * self.err;
+            if e2 >= self.dy {
+                self.err += self.dy;
+                self.x += self.sx;
+            }
+            if e2 <= self.dx {
+                self.err += self.dx;
+                self.y += self.sy;
+            }
+        }
+        Some((x, y, self.array.at((x, y))))
+    }
+}
+
+pub trait Point2 {
+    fn x(&self) -> u32;
+    fn y(&self) -> u32;
+}
+
+impl Point2 for (u32, u32) {
+    fn x(&self) -> u32 { self.0 }
+    fn y(&self) -> u32 { self.1 }
+}
+
+impl Point2 for [u32; 2] {
+    fn x(&self) -> u32 { self[0] }
+    fn y(&self) -> u32 { self[1] }
 }
 
 impl<P: Point2, T> Index<P> for Array2<T> {
     type Output = T;
     
     fn index(&self, point: P) -> &Self::Output {
-        let x = point.x();
-        let y = point.y();
-        if x < self.width && y < self.height {
-            unsafe { &*self.ptr.offset(x as isize + y as isize * self.width as isize) }
-        } else {
-            panic!("Array2 index out of bounds")
-        }
+        self.at(point)
     }
 }
 
 impl<P: Point2, T> IndexMut<P> for Array2<T> {
     fn index_mut(&mut self, point: P) -> &mut Self::Output {
-        let x = point.x();
-        let y = point.y();
-        if x < self.width && y < self.height {
-            unsafe { &mut *self.ptr.offset(x as isize + y as isize * self.width as isize) }
-        } else {
-            panic!("Array2 index out of bounds")
+        self.at_mut(point)
+    }
+}
+
+/// The allocation of an array whose first `written` elements are initialized. Dropping it drops
+/// only those elements and frees the allocation, which cleans up after a failed decode.
+struct PartialArray<T> {
+    ptr: *mut T,
+    bytes: usize,
+    written: usize
+}
+
+impl<T> Drop for PartialArray<T> {
+    fn drop(&mut self) {
+        for i in 0..self.written as isize {
+            unsafe { ptr::drop_in_place(self.ptr.offset(i)); }
+        }
+        if self.bytes > 0 {
+            unsafe { heap::deallocate(self.ptr as *mut u8, self.bytes, mem::align_of::<T>()); }
         }
     }
 }
@@ -395,26 +1821,28 @@ impl<T: Decodable> Decodable for Array2<T> {
         d.read_struct("Array2", 3, |d| {
             let width = try!(d.read_struct_field("width", 0, |d| d.read_u32()));
             let height = try!(d.read_struct_field("height", 1, |d| d.read_u32()));
-            let ptr = try!(d.read_struct_field("data", 2, |d| {
+            d.read_struct_field("data", 2, |d| {
                 d.read_seq(|d, len| {
-                    let bytes = len * mem::size_of::<T>();
-                    let ptr = unsafe { Unique::new(heap::allocate(bytes, mem::align_of::<T>()) as *mut T) };
+                    if len != width as usize * height as usize {
+                        return Err(d.error("Array2 data length does not match its dimensions"));
+                    }
+                    let array = match Array2::<T>::build(width, height, Init::Uninit) {
+                        Ok(array) => array,
+                        Err(BuildError::Overflow) => return Err(d.error("Array2 dimensions exceed the maximum allocation size")),
+                        Err(BuildError::AllocFailed) => return Err(d.error("Array2 allocation failed"))
+                    };
+                    let mut partial = PartialArray { ptr: *array.ptr, bytes: array.allocated_bytes(), written: 0 };
+                    mem::forget(array);
                     for i in 0..len {
-                        match d.read_seq_elt(i, |d| Decodable::decode(d)) {
-                            Ok(e) => unsafe { ptr::write(ptr.offset(i as isize), e) },
-                            Err(e) => {
-                                for j in 0..i { 
-                                     unsafe { ptr::read(ptr.offset(j as isize)); }
-                                }
-                                unsafe { heap::deallocate(*ptr as *mut u8, bytes, mem::align_of::<T>()); }
-                                return Err(e);
-                            }
-                        }
+                        let e = try!(d.read_seq_elt(i, |d| Decodable::decode(d)));
+                        unsafe { ptr::write(partial.ptr.offset(i as isize), e) }
+                        partial.written += 1;
                     }
-                    Ok(ptr)
+                    let array = Array2 { ptr: unsafe { Unique::new(partial.ptr) }, width: width, height: height };
+                    mem::forget(partial);
+                    Ok(array)
                 })
-            }));
-            Ok(Array2 { width: width, height: height, ptr: ptr })
+            })
         })
     }
 }
@@ -481,13 +1909,186 @@ impl<T: Ord> Ord for Array2<T> {
     }
 }
 
+/// Returns the sum of the elements inside the given rectangle, using a table built by `summed_area_table`.
+/// Parts of the rectangle that exceed the table bounds are ignored, as with `view`.
+pub fn rect_sum(table: &Array2<u64>, x: u32, y: u32, width: u32, height: u32) -> u64 {
+    match table.clamped_rect(x, y, width, height) {
+        Some((x0, y0, width, height)) if width > 0 && height > 0 => {
+            let (x1, y1) = (x0 + width - 1, y0 + height - 1);
+            let left = if x0 > 0 { table[(x0 - 1, y1)] } else { 0 };
+            let above = if y0 > 0 { table[(x1, y0 - 1)] } else { 0 };
+            let above_left = if x0 > 0 && y0 > 0 { table[(x0 - 1, y0 - 1)] } else { 0 };
+            table[(x1, y1)] + above_left - left - above
+        }
+        _ => 0
+    }
+}
+
+/// A pair of equally sized arrays for simulations that read the current state from the front buffer
+/// while writing the next state into the back buffer.
+///
+/// The back buffer is only exposed as a slice of its elements, so its dimensions cannot be changed
+/// and both buffers always have the same size.
+pub struct DoubleBuffer<T> {
+    front: Array2<T>,
+    back: Array2<T>
+}
+
+impl<T: Clone> DoubleBuffer<T> {
+    /// Constructs a `DoubleBuffer<T>` from `width` and `height` by repeatedly calling `f`.
+    /// Both buffers start out with the same elements.
+    pub fn from_fn<F: FnMut() -> T>(width: u32, height: u32, f: F) -> DoubleBuffer<T> {
+        let front = Array2::from_fn(width, height, f);
+        let mut iter = front.iter();
+        let back = Array2::from_fn(width, height, || iter.next().unwrap().clone());
+        DoubleBuffer { front: front, back: back }
+    }
+}
+
+impl<T> DoubleBuffer<T> {
+    /// Returns a reference to the front buffer.
+    pub fn front(&self) -> &Array2<T> {
+        &self.front
+    }
+    
+    /// Returns the elements of the back buffer in row-major order as a mutable slice.
+    pub fn back_mut(&mut self) -> &mut [T] {
+        self.back.as_slice_mut()
+    }
+    
+    /// Returns a reference to the front buffer together with the elements of the back buffer
+    /// in row-major order as a mutable slice.
+    pub fn split(&mut self) -> (&Array2<T>, &mut [T]) {
+        (&self.front, self.back.as_slice_mut())
+    }
+    
+    /// Swaps the front and back buffers in constant time.
+    pub fn swap(&mut self) {
+        self.front.swap_with(&mut self.back);
+    }
+}
+
+/// An array whose width and height are known to be equal.
+/// Dereferences to `Array2<T>` for read access; mutation is limited to operations that keep it square,
+/// such as writing elements or transposing.
+pub struct SquareArray2<T> {
+    array: Array2<T>
+}
+
+impl<T> SquareArray2<T> {
+    /// Wraps `array` if its width and height are equal, otherwise returns `Err` with the array.
+    pub fn new(array: Array2<T>) -> Result<SquareArray2<T>, Array2<T>> {
+        array.try_into_square().map(|array| SquareArray2 { array: array })
+    }
+    
+    /// Returns the width and height of the array.
+    pub fn side(&self) -> u32 {
+        self.array.width
+    }
+    
+    /// Returns a mutable reference to the element at the given position, or `None` if the position is invalid.
+    pub fn get_mut(&mut self, x: u32, y: u32) -> Option<&mut T> {
+        self.array.get_mut(x, y)
+    }
+    
+    /// Returns a mutable reference to the element at the given position.
+    ///
+    /// # Panics
+    /// Panics if the position is out of bounds.
+    pub fn at_mut<P: Point2>(&mut self, point: P) -> &mut T {
+        self.array.at_mut(point)
+    }
+    
+    /// Returns a mutable iterator over the elements of the array.
+    pub fn iter_mut(&mut self) -> ItemsMut<T> {
+        self.array.iter_mut()
+    }
+    
+    /// Returns a mutable slice over all elements in the array.
+    pub fn as_slice_mut(&mut self) -> &mut [T] {
+        self.array.as_slice_mut()
+    }
+    
+    /// Transposes the array in place.
+    pub fn transpose_in_place(&mut self) {
+        self.array.transpose_in_place();
+    }
+    
+    /// Returns the wrapped array.
+    pub fn into_inner(self) -> Array2<T> {
+        self.array
+    }
+}
+
+impl<T> Deref for SquareArray2<T> {
+    type Target = Array2<T>;
+    
+    fn deref(&self) -> &Array2<T> {
+        &self.array
+    }
+}
+
+impl<P: Point2, T> Index<P> for SquareArray2<T> {
+    type Output = T;
+    
+    fn index(&self, point: P) -> &Self::Output {
+        self.array.at(point)
+    }
+}
+
+impl<P: Point2, T> IndexMut<P> for SquareArray2<T> {
+    fn index_mut(&mut self, point: P) -> &mut Self::Output {
+        self.array.at_mut(point)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::Array2;
+    use super::{rect_sum, Array2, BuildError, DoubleBuffer, Init, IntoIter, SquareArray2};
+    use rustc_serialize::json;
+    use std::cell::Cell;
+    use std::cmp;
+    use std::mem::{self, MaybeUninit};
     
     #[derive(Copy, Clone, PartialEq, Debug)]
     struct ZeroSizedType;
    
+    thread_local!(static LIVE: Cell<isize> = Cell::new(0));
+    
+    fn live() -> isize { LIVE.with(|l| l.get()) }
+    
+    // Keeps track of how many values are alive on the current thread.
+    #[derive(Debug, PartialEq)]
+    struct Tracked(u8);
+    
+    impl Tracked {
+        fn new(value: u8) -> Tracked {
+            LIVE.with(|l| l.set(l.get() + 1));
+            Tracked(value)
+        }
+    }
+    
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            LIVE.with(|l| l.set(l.get() - 1));
+        }
+    }
+    
+    struct TrackedZst;
+    
+    impl TrackedZst {
+        fn new() -> TrackedZst {
+            LIVE.with(|l| l.set(l.get() + 1));
+            TrackedZst
+        }
+    }
+    
+    impl Drop for TrackedZst {
+        fn drop(&mut self) {
+            LIVE.with(|l| l.set(l.get() - 1));
+        }
+    }
+   
     fn standard_array() -> Array2<u8> {
         let mut count = 0;
         Array2::from_fn(2, 2, || {
@@ -888,4 +2489,1502 @@ mod test {
         assert_eq!(iter.next(), Some(&mut [ZeroSizedType, ZeroSizedType][..]));
         assert_eq!(iter.next(), None);
     }
+    
+    #[test]
+    fn nested_vec() {
+        let array = standard_array();
+        let nested = array.to_nested_vec();
+        assert_eq!(nested, vec![vec![0, 1], vec![2, 3]]);
+        assert_eq!(Array2::from_nested_vec(nested), Some(array));
+        
+        let array = Array2::from_nested_vec(vec![vec![0u8, 1, 2], vec![3, 4, 5]]).unwrap();
+        assert_eq!(array.width(), 3);
+        assert_eq!(array.height(), 2);
+        assert_eq!(array.as_slice(), &[0, 1, 2, 3, 4, 5][..]);
+        
+        let array = Array2::<u8>::from_nested_vec(vec![]).unwrap();
+        assert_eq!(array.width(), 0);
+        assert_eq!(array.height(), 0);
+        
+        assert_eq!(zero_width_array().to_nested_vec(), vec![Vec::<u8>::new(); 2]);
+        assert_eq!(Array2::from_nested_vec(zst_array().to_nested_vec()), Some(zst_array()));
+    }
+    
+    #[test]
+    fn nested_vec_ragged() {
+        assert_eq!(Array2::from_nested_vec(vec![vec![0u8, 1], vec![2]]), None);
+        assert_eq!(Array2::from_nested_vec(vec![vec![0u8], vec![1, 2]]), None);
+    }
+    
+    #[test]
+    fn view_len() {
+        let array = Array2::from_elem(4, 4, 0u8);
+        assert_eq!(array.view(0, 0, 4, 4).len(), 4);
+        assert_eq!(array.view(1, 2, 2, 5).len(), 2);
+        assert_eq!(array.view(4, 0, 1, 1).len(), 0);
+        assert_eq!(array.view(0, 0, 0, 1).len(), 0);
+        
+        let mut iter = array.view(3, 1, 2, 2);
+        assert_eq!(iter.len(), 2);
+        iter.next();
+        assert_eq!(iter.len(), 1);
+        iter.next_back();
+        assert_eq!(iter.len(), 0);
+        
+        let mut array = Array2::from_elem(4, 4, 0u8);
+        assert_eq!(array.view_mut(1, 2, 2, 5).len(), 2);
+        assert_eq!(zero_width_array().view(0, 0, 1, 1).len(), 0);
+        assert_eq!(zst_array().view(0, 1, 3, 3).len(), 1);
+    }
+    
+    #[test]
+    fn view_rev() {
+        // Array:
+        // [0, 1]
+        // [2, 3]
+        let array = standard_array();
+        let mut iter = array.view(1, 0, 1, 2).rev();
+        assert_eq!(iter.next(), Some(&[3][..]));
+        assert_eq!(iter.next(), Some(&[1][..]));
+        assert_eq!(iter.next(), None);
+        
+        let mut iter = array.view(0, 0, 2, 2);
+        assert_eq!(iter.next_back(), Some(&[2, 3][..]));
+        assert_eq!(iter.next(), Some(&[0, 1][..]));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+        
+        let mut array = standard_array();
+        {
+            let mut iter = array.view_mut(0, 0, 1, 2).rev();
+            assert_eq!(iter.next(), Some(&mut [2][..]));
+            assert_eq!(iter.next(), Some(&mut [0][..]));
+            assert_eq!(iter.next(), None);
+        }
+        
+        // Views touching the first or last row of a wider array must not step outside the allocation.
+        let mut n = 0u8;
+        let mut array = Array2::from_fn(4, 3, || { n += 1; n - 1 });
+        let mut iter = array.view(2, 0, 2, 3);
+        assert_eq!(iter.next_back(), Some(&[10, 11][..]));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next(), Some(&[2, 3][..]));
+        assert_eq!(iter.next_back(), Some(&[6, 7][..]));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(array.view(1, 1, 1, 2).rev().collect::<Vec<_>>(), vec![&[9][..], &[5][..]]);
+        for row in array.view_mut(3, 0, 1, 3).rev() {
+            row[0] = 0;
+        }
+        assert_eq!(array.as_slice(), &[0, 1, 2, 0, 4, 5, 6, 0, 8, 9, 10, 0]);
+        
+        let array = zst_array();
+        let mut iter = array.view(0, 0, 3, 3);
+        assert_eq!(iter.next_back(), Some(&[ZeroSizedType, ZeroSizedType][..]));
+        assert_eq!(iter.next_back(), Some(&[ZeroSizedType, ZeroSizedType][..]));
+        assert_eq!(iter.next_back(), None);
+    }
+    
+    #[test]
+    fn clear_to() {
+        let mut array = standard_array();
+        let ptr = array.as_slice().as_ptr();
+        array.clear_to(7);
+        assert_eq!(array.as_slice(), &[7, 7, 7, 7][..]);
+        assert_eq!(array.as_slice().as_ptr(), ptr);
+        
+        let mut array = Array2::from_elem(3, 2, String::from("old"));
+        let ptr = array.as_slice().as_ptr();
+        array.clear_to(String::from("new"));
+        assert!(array.iter().all(|s| s == "new"));
+        assert_eq!(array.as_slice().as_ptr(), ptr);
+        
+        let mut array = zero_width_array();
+        array.clear_to(1);
+        assert_eq!(array.width(), 0);
+        assert_eq!(array.height(), 2);
+    }
+    
+    #[test]
+    fn capacity() {
+        assert_eq!(zst_array().capacity(), 4);
+        
+        // `capacity` cannot observe the allocation, so the exact-size guarantee is enforced at the
+        // allocator boundary instead: arrays are freed with the size computed from their current
+        // dimensions, and Miri reports any deallocation whose size differs from the allocation.
+        // Every path below changes the dimensions of an existing allocation before freeing it.
+        let mut array = Array2::from_elem(2, 2, 0u64);
+        array.resize_with(5, 3, |_, _| 1);
+        array.resize_with(1, 2, |_, _| unreachable!());
+        assert_eq!(array.extend_rows(vec![vec![2], vec![3]]), Ok(()));
+        let array = array.into_transposed();
+        let mut iter = array.into_iter();
+        iter.next();
+    }
+    
+    #[test]
+    fn at() {
+        let array = standard_array();
+        let points = vec![(1, 0), (0, 1), (1, 1)];
+        let elements: Vec<u8> = points.iter().map(|&(x, y)| *array.at((x, y))).collect();
+        assert_eq!(elements, vec![1, 2, 3]);
+        assert_eq!(*array.at([1, 1]), array[(1, 1)]);
+        
+        let mut array = standard_array();
+        let points = vec![(0, 0), (1, 1)];
+        let elements: Vec<u8> = points.into_iter().map(|p| { *array.at_mut(p) += 10; *array.at(p) }).collect();
+        assert_eq!(elements, vec![10, 13]);
+        assert_eq!(array.as_slice(), &[10, 1, 2, 13][..]);
+        
+        assert_eq!(*zst_array().at((1, 1)), ZeroSizedType);
+    }
+    
+    #[test]
+    #[should_panic(expected = "the position is (2, 0) but the dimensions are 2x2")]
+    fn at_panic() {
+        standard_array().at((2, 0));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn at_mut_panic() {
+        standard_array().at_mut((0, 2));
+    }
+    
+    #[test]
+    fn copy() {
+        let array = standard_array();
+        let copy = array.copy();
+        assert_eq!(copy, array);
+        assert!(copy.as_slice().as_ptr() != array.as_slice().as_ptr());
+        
+        let mut n = 0u32;
+        let array = Array2::from_fn(1000, 1000, || { n += 1; (n % 251) as u8 });
+        assert_eq!(array.copy(), array);
+        
+        assert_eq!(zero_width_array().copy(), zero_width_array());
+        assert_eq!(zero_height_array().copy(), zero_height_array());
+        assert_eq!(zst_array().copy(), zst_array());
+    }
+    
+    #[test]
+    fn extend_rows() {
+        let mut array = Array2::from_nested_vec(vec![vec![0u8, 1, 2]]).unwrap();
+        assert_eq!(array.extend_rows(vec![vec![3, 4, 5], vec![6, 7, 8]]), Ok(()));
+        assert_eq!(array.width(), 3);
+        assert_eq!(array.height(), 3);
+        assert_eq!(array.as_slice(), &[0, 1, 2, 3, 4, 5, 6, 7, 8][..]);
+        
+        let mut array = Array2::from_elem(2, 0, String::new());
+        assert_eq!(array.extend_rows(vec![vec!["a".to_string(), "b".to_string()]]), Ok(()));
+        assert_eq!(array.to_nested_vec(), vec![vec!["a".to_string(), "b".to_string()]]);
+        
+        let mut array = zero_width_array();
+        assert_eq!(array.extend_rows(vec![vec![]]), Ok(()));
+        assert_eq!(array.height(), 3);
+        
+        let mut array = zst_array();
+        assert_eq!(array.extend_rows(vec![vec![ZeroSizedType; 2]]), Ok(()));
+        assert_eq!(array.height(), 3);
+        assert_eq!(array.iter().count(), 6);
+    }
+    
+    #[test]
+    fn extend_rows_mismatch() {
+        let mut array = Array2::from_nested_vec(vec![vec![0u8, 1, 2]]).unwrap();
+        assert_eq!(array.extend_rows(vec![vec![3, 4, 5], vec![6, 7]]), Err(()));
+        assert_eq!(array.extend_rows(vec![vec![3, 4, 5, 6]]), Err(()));
+        assert_eq!(array.extend_rows(::std::iter::once(::std::iter::repeat(0))), Err(()));
+        assert_eq!(array.height(), 1);
+        assert_eq!(array.as_slice(), &[0, 1, 2][..]);
+    }
+    
+    #[test]
+    fn border() {
+        // Array:
+        // [0, 1, 2]
+        // [3, 4, 5]
+        // [6, 7, 8]
+        let array = Array2::from_nested_vec(vec![vec![0u8, 1, 2], vec![3, 4, 5], vec![6, 7, 8]]).unwrap();
+        let border: Vec<_> = array.border().collect();
+        assert_eq!(border, vec![(0, 0, &0), (1, 0, &1), (2, 0, &2), (2, 1, &5),
+                                (2, 2, &8), (1, 2, &7), (0, 2, &6), (0, 1, &3)]);
+        
+        let array = Array2::from_elem(1, 1, 0u8);
+        assert_eq!(array.border().collect::<Vec<_>>(), vec![(0, 0, &0)]);
+        
+        let array = Array2::from_nested_vec(vec![vec![0u8], vec![1], vec![2], vec![3]]).unwrap();
+        let border: Vec<_> = array.border().collect();
+        assert_eq!(border, vec![(0, 0, &0), (0, 1, &1), (0, 2, &2), (0, 3, &3)]);
+        
+        let array = Array2::from_nested_vec(vec![vec![0u8, 1, 2, 3]]).unwrap();
+        let border: Vec<_> = array.border().map(|(x, y, _)| (x, y)).collect();
+        assert_eq!(border, vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+        
+        let array = Array2::from_elem(4, 3, 0u8);
+        let mut points: Vec<_> = array.border().map(|(x, y, _)| (x, y)).collect();
+        assert_eq!(array.border().len(), 10);
+        points.sort();
+        points.dedup();
+        assert_eq!(points.len(), 10);
+        
+        assert_eq!(zero_width_array().border().next(), None);
+        assert_eq!(zero_height_array().border().next(), None);
+        assert_eq!(zst_array().border().count(), 4);
+    }
+    
+    #[test]
+    fn transpose_in_place() {
+        let mut array = Array2::from_nested_vec(vec![vec![0u8, 1, 2], vec![3, 4, 5], vec![6, 7, 8]]).unwrap();
+        array.transpose_in_place();
+        assert_eq!(array.to_nested_vec(), vec![vec![0, 3, 6], vec![1, 4, 7], vec![2, 5, 8]]);
+        
+        let mut array = Array2::from_nested_vec(vec![vec!["a".to_string(), "b".to_string()],
+                                                     vec!["c".to_string(), "d".to_string()]]).unwrap();
+        array.transpose_in_place();
+        assert_eq!(array.as_slice(), &["a", "c", "b", "d"][..]);
+        
+        let mut array = Array2::from_elem(0, 0, 0u8);
+        array.transpose_in_place();
+        let mut array = zst_array();
+        array.transpose_in_place();
+    }
+    
+    #[test]
+    #[should_panic]
+    fn transpose_in_place_panic() {
+        Array2::from_elem(3, 2, 0u8).transpose_in_place();
+    }
+    
+    #[test]
+    fn into_iter() {
+        let array = standard_array();
+        let mut iter = array.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+        
+        let array = Array2::from_elem(3, 2, String::from("a"));
+        let vec: Vec<String> = array.into_iter().collect();
+        assert_eq!(vec.len(), 6);
+        
+        let array = Array2::from_elem(3, 2, String::from("a"));
+        let mut iter = array.into_iter();
+        iter.next();
+        assert_eq!(iter.size_hint(), (5, Some(5)));
+        
+        assert_eq!(zero_width_array().into_iter().len(), 0);
+        assert_eq!(zero_height_array().into_iter().next(), None);
+        let mut iter = zst_array().into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(ZeroSizedType));
+        assert_eq!(iter.len(), 3);
+    }
+    
+    #[test]
+    fn row_range() {
+        let array = standard_array();
+        assert_eq!(array.row_range(0), Some(0..2));
+        assert_eq!(array.row_range(1), Some(2..4));
+        assert_eq!(array.row_range(2), None);
+        assert_eq!(&array.as_slice()[array.row_range(1).unwrap()], &[2, 3][..]);
+        
+        assert_eq!(zero_width_array().row_range(1), Some(0..0));
+        assert_eq!(zero_height_array().row_range(0), None);
+    }
+    
+    #[test]
+    fn row_ptr() {
+        let array = standard_array();
+        unsafe {
+            assert_eq!(array.row_ptr(0), array.as_slice().as_ptr());
+            assert_eq!(array.row_ptr(1), array.as_slice()[2..].as_ptr());
+            assert_eq!(*array.row_ptr(1).offset(1), 3);
+        }
+    }
+    
+    #[test]
+    fn fill_rect() {
+        let mut array = Array2::from_elem(4, 4, 0u8);
+        array.fill_rect(1, 1, 2, 2, 1);
+        assert_eq!(array.to_nested_vec(), vec![vec![0, 0, 0, 0],
+                                               vec![0, 1, 1, 0],
+                                               vec![0, 1, 1, 0],
+                                               vec![0, 0, 0, 0]]);
+        
+        array.fill_rect(3, 2, 5, 5, 2);
+        array.fill_rect(4, 0, 1, 1, 3);
+        assert_eq!(array.to_nested_vec(), vec![vec![0, 0, 0, 0],
+                                               vec![0, 1, 1, 0],
+                                               vec![0, 1, 1, 2],
+                                               vec![0, 0, 0, 2]]);
+        
+        let mut array = Array2::from_elem(3, 3, String::from("a"));
+        array.fill_rect(0, 1, 3, 1, String::from("b"));
+        assert_eq!(array.as_slice(), &["a", "a", "a", "b", "b", "b", "a", "a", "a"][..]);
+    }
+    
+    #[test]
+    fn line() {
+        let array = Array2::from_elem(5, 5, 0u8);
+        let points = |x0, y0, x1, y1| array.line(x0, y0, x1, y1).map(|(x, y, _)| (x, y)).collect::<Vec<_>>();
+        assert_eq!(points(1, 2, 4, 2), vec![(1, 2), (2, 2), (3, 2), (4, 2)]);
+        assert_eq!(points(4, 2, 1, 2), vec![(4, 2), (3, 2), (2, 2), (1, 2)]);
+        assert_eq!(points(3, 0, 3, 3), vec![(3, 0), (3, 1), (3, 2), (3, 3)]);
+        assert_eq!(points(0, 0, 4, 4), vec![(0, 0), (1, 1), (2, 2), (3, 3), (4, 4)]);
+        assert_eq!(points(4, 0, 0, 4), vec![(4, 0), (3, 1), (2, 2), (1, 3), (0, 4)]);
+        assert_eq!(points(0, 0, 4, 2), vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]);
+        assert_eq!(points(2, 2, 2, 2), vec![(2, 2)]);
+        assert_eq!(points(0, 0, 5, 0), vec![]);
+        assert_eq!(points(0, 5, 0, 0), vec![]);
+        
+        let array = standard_array();
+        let elements: Vec<_> = array.line(0, 0, 1, 1).map(|(_, _, e)| *e).collect();
+        assert_eq!(elements, vec![0, 3]);
+        assert_eq!(zero_width_array().line(0, 0, 0, 0).next(), None);
+    }
+    
+    #[test]
+    fn swap_with() {
+        let mut a = standard_array();
+        let mut b = Array2::from_elem(3, 3, 9u8);
+        a.swap_with(&mut b);
+        assert_eq!((a.width(), a.height()), (3, 3));
+        assert_eq!((b.width(), b.height()), (2, 2));
+        assert_eq!(a, Array2::from_elem(3, 3, 9));
+        assert_eq!(b, standard_array());
+        
+        let mut c = zero_width_array();
+        c.swap_with(&mut b);
+        assert_eq!(c, standard_array());
+        assert_eq!(b, zero_width_array());
+    }
+    
+    #[test]
+    fn double_buffer() {
+        let mut n = 0u8;
+        let mut buffer = DoubleBuffer::from_fn(2, 2, || { n += 1; n - 1 });
+        assert_eq!(*buffer.front(), standard_array());
+        assert_eq!(buffer.back_mut(), standard_array().as_slice());
+        
+        {
+            let (front, back) = buffer.split();
+            for (dst, src) in back.iter_mut().zip(front.iter()) {
+                *dst = *src + 10;
+            }
+        }
+        assert_eq!(buffer.front().as_slice(), &[0, 1, 2, 3][..]);
+        buffer.swap();
+        assert_eq!(buffer.front().as_slice(), &[10, 11, 12, 13][..]);
+        assert_eq!(buffer.back_mut(), &[0, 1, 2, 3][..]);
+        buffer.back_mut()[1] = 5;
+        buffer.swap();
+        assert_eq!(buffer.front().dimensions(), (2, 2));
+        assert_eq!(buffer.front()[(1, 0)], 5);
+    }
+    
+    #[test]
+    fn rotate_rows() {
+        let nested = vec![vec![0u8, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+        let mut array = Array2::from_nested_vec(nested.clone()).unwrap();
+        array.rotate_rows(1);
+        assert_eq!(array.to_nested_vec(), vec![vec![6, 7, 8], vec![0, 1, 2], vec![3, 4, 5]]);
+        array.rotate_rows(2);
+        assert_eq!(array.to_nested_vec(), nested);
+        array.rotate_rows(3);
+        assert_eq!(array.to_nested_vec(), nested);
+        array.rotate_rows(4);
+        assert_eq!(array.to_nested_vec(), vec![vec![6, 7, 8], vec![0, 1, 2], vec![3, 4, 5]]);
+        
+        let mut array = zero_height_array();
+        array.rotate_rows(1);
+        let mut array = zero_width_array();
+        array.rotate_rows(1);
+    }
+    
+    #[test]
+    fn rotate_cols() {
+        let nested = vec![vec![0u8, 1, 2], vec![3, 4, 5], vec![6, 7, 8]];
+        let mut array = Array2::from_nested_vec(nested.clone()).unwrap();
+        array.rotate_cols(1);
+        assert_eq!(array.to_nested_vec(), vec![vec![2, 0, 1], vec![5, 3, 4], vec![8, 6, 7]]);
+        array.rotate_cols(2);
+        assert_eq!(array.to_nested_vec(), nested);
+        array.rotate_cols(3);
+        assert_eq!(array.to_nested_vec(), nested);
+        
+        let mut array = zero_width_array();
+        array.rotate_cols(1);
+        let mut array = zero_height_array();
+        array.rotate_cols(1);
+    }
+    
+    #[test]
+    fn min_max() {
+        // Array:
+        // [4, 9, 2]
+        // [7, 1, 5]
+        let array = Array2::from_nested_vec(vec![vec![4u8, 9, 2], vec![7, 1, 5]]).unwrap();
+        assert_eq!(array.min_element(), Some(&1));
+        assert_eq!(array.max_element(), Some(&9));
+        assert_eq!(array.argmin(), Some((1, 1)));
+        assert_eq!(array.argmax(), Some((1, 0)));
+        assert_eq!(array.max_by_key(|&e| e % 5), Some((0, 0, &4)));
+        assert_eq!(array.min_by_key(|&e| e % 5), Some((2, 1, &5)));
+        
+        assert_eq!(zero_width_array().min_element(), None);
+        assert_eq!(zero_height_array().argmax(), None);
+    }
+    
+    #[test]
+    fn min_max_ties() {
+        // Array:
+        // [1, 3]
+        // [3, 1]
+        let array = Array2::from_nested_vec(vec![vec![1u8, 3], vec![3, 1]]).unwrap();
+        assert_eq!(array.argmin(), Some((0, 0)));
+        assert_eq!(array.argmax(), Some((1, 0)));
+        
+        let array = Array2::from_nested_vec(vec![vec![(0, 'a'), (1, 'b')], vec![(1, 'c'), (0, 'd')]]).unwrap();
+        assert_eq!(array.max_by_key(|e| e.0), Some((1, 0, &(1, 'b'))));
+        assert_eq!(array.min_by_key(|e| e.0), Some((0, 0, &(0, 'a'))));
+        
+        assert_eq!(zst_array().max_by_key(|_| 0), Some((0, 0, &ZeroSizedType)));
+    }
+    
+    #[test]
+    fn row_slices() {
+        let array = Array2::from_nested_vec(vec![vec![0u8, 1, 2], vec![3, 4, 5]]).unwrap();
+        let rows = array.row_slices();
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.len() == 3));
+        assert_eq!(rows, vec![&[0, 1, 2][..], &[3, 4, 5][..]]);
+        assert_eq!(rows[1].as_ptr(), array.as_slice()[3..].as_ptr());
+        
+        let array = zero_width_array();
+        assert_eq!(array.row_slices(), vec![&[][..], &[][..]]);
+        assert_eq!(zero_height_array().row_slices().len(), 0);
+        assert_eq!(zst_array().row_slices().len(), 2);
+    }
+    
+    #[test]
+    fn checked_construction() {
+        let array = Array2::checked_from_elem(3, 2, 1u8).unwrap();
+        assert_eq!(array, Array2::from_elem(3, 2, 1u8));
+        let mut n = 0u8;
+        let array = Array2::checked_from_fn(2, 2, || { n += 1; n - 1 }).unwrap();
+        assert_eq!(array, standard_array());
+        assert_eq!(Array2::checked_from_elem(0, 2, 0u8), Some(zero_width_array()));
+        
+        let max = ::std::u32::MAX;
+        assert_eq!(Array2::checked_from_elem(max, max, 0u64), None);
+        assert_eq!(Array2::checked_from_fn(max, max, || -> u8 { unreachable!() }), None);
+        assert!(Array2::checked_from_elem(100, 100, ZeroSizedType).is_some());
+    }
+    
+    #[test]
+    fn with_points() {
+        let array = Array2::from_nested_vec(vec![vec![0u8, 1, 2], vec![3, 4, 5]]).unwrap();
+        let points: Vec<_> = array.iter().with_points().collect();
+        assert_eq!(points, vec![(0, 0, &0), (1, 0, &1), (2, 0, &2), (0, 1, &3), (1, 1, &4), (2, 1, &5)]);
+        
+        let mut array = Array2::from_elem(3, 2, 0u32);
+        for (x, y, e) in array.iter_mut().with_points() {
+            *e = x * 10 + y;
+        }
+        assert_eq!(array.as_slice(), &[0, 10, 20, 1, 11, 21][..]);
+        
+        let filtered: Vec<_> = array.iter().with_points().filter(|&(_, y, _)| y == 1).map(|(x, _, _)| x).collect();
+        assert_eq!(filtered, vec![0, 1, 2]);
+        
+        assert_eq!(zero_width_array().iter().with_points().next(), None);
+        assert_eq!(zst_array().iter().with_points().last(), Some((1, 1, &ZeroSizedType)));
+    }
+    
+    #[test]
+    fn resize_with() {
+        let mut array = standard_array();
+        array.resize_with(4, 4, |x, y| (x * 10 + y) as u8);
+        assert_eq!(array.to_nested_vec(), vec![vec![0, 1, 20, 30],
+                                               vec![2, 3, 21, 31],
+                                               vec![2, 12, 22, 32],
+                                               vec![3, 13, 23, 33]]);
+        
+        array.resize_with(3, 1, |_, _| unreachable!());
+        assert_eq!(array.to_nested_vec(), vec![vec![0, 1, 20]]);
+        
+        let mut array = Array2::from_nested_vec(vec![vec!["a".to_string(), "b".to_string()],
+                                                     vec!["c".to_string(), "d".to_string()]]).unwrap();
+        array.resize_with(1, 3, |x, y| format!("{}{}", x, y));
+        assert_eq!(array.as_slice(), &["a", "c", "02"][..]);
+        
+        array.resize_with(0, 3, |_, _| unreachable!());
+        assert_eq!((array.width(), array.height()), (0, 3));
+        array.resize_with(2, 1, |x, y| format!("{}{}", x, y));
+        assert_eq!(array.as_slice(), &["00", "10"][..]);
+    }
+    
+    #[test]
+    fn trim() {
+        // Array:
+        // [0, 0, 0, 0]
+        // [0, 1, 0, 0]
+        // [0, 0, 2, 0]
+        // [0, 0, 0, 0]
+        let array = Array2::from_nested_vec(vec![vec![0u8, 0, 0, 0], vec![0, 1, 0, 0],
+                                                 vec![0, 0, 2, 0], vec![0, 0, 0, 0]]).unwrap();
+        let trimmed = array.trim(|&e| e == 0);
+        assert_eq!(trimmed.to_nested_vec(), vec![vec![1, 0], vec![0, 2]]);
+        
+        let trimmed = array.trim(|&e| e != 2);
+        assert_eq!(trimmed.to_nested_vec(), vec![vec![2]]);
+        
+        assert_eq!(standard_array().trim(|_| false), standard_array());
+    }
+    
+    #[test]
+    fn trim_all_blank() {
+        let trimmed = Array2::from_elem(3, 3, 0u8).trim(|&e| e == 0);
+        assert_eq!((trimmed.width(), trimmed.height()), (0, 0));
+        
+        let trimmed = zero_width_array().trim(|_| false);
+        assert_eq!((trimmed.width(), trimmed.height()), (0, 0));
+    }
+    
+    #[test]
+    fn mirror_h_into() {
+        let mut array = Array2::from_nested_vec(vec![vec![0u8, 1, 2, 3], vec![4, 5, 6, 7]]).unwrap();
+        array.mirror_h_into();
+        assert_eq!(array.to_nested_vec(), vec![vec![0, 1, 1, 0], vec![4, 5, 5, 4]]);
+        
+        let mut array = Array2::from_nested_vec(vec![vec![0u8, 1, 2], vec![3, 4, 5]]).unwrap();
+        array.mirror_h_into();
+        assert_eq!(array.to_nested_vec(), vec![vec![0, 1, 0], vec![3, 4, 3]]);
+    }
+    
+    #[test]
+    fn mirror_v_into() {
+        let mut array = Array2::from_nested_vec(vec![vec![0u8, 1, 2, 3], vec![4, 5, 6, 7]]).unwrap();
+        array.mirror_v_into();
+        assert_eq!(array.to_nested_vec(), vec![vec![0, 1, 2, 3], vec![0, 1, 2, 3]]);
+        
+        let mut array = Array2::from_nested_vec(vec![vec![0u8, 1], vec![2, 3], vec![4, 5]]).unwrap();
+        array.mirror_v_into();
+        assert_eq!(array.to_nested_vec(), vec![vec![0, 1], vec![2, 3], vec![0, 1]]);
+        
+        let mut array = zero_width_array();
+        array.mirror_v_into();
+        array.mirror_h_into();
+    }
+    
+    #[test]
+    fn count_neighbors8_where() {
+        // Array:
+        // [1, 1, 0, 0]
+        // [0, 1, 1, 0]
+        // [1, 0, 1, 1]
+        let array = Array2::from_nested_vec(vec![vec![1u8, 1, 0, 0], vec![0, 1, 1, 0], vec![1, 0, 1, 1]]).unwrap();
+        let alive = |&e: &u8| e == 1;
+        assert_eq!(array.count_neighbors8_where(1, 1, &alive), 5);
+        assert_eq!(array.count_neighbors8_where(2, 1, &alive), 4);
+        assert_eq!(array.count_neighbors8_where(3, 1, &alive), 3);
+        assert_eq!(array.count_neighbors8_where(0, 0, &alive), 2);
+        assert_eq!(array.count_neighbors8_where(3, 2, &alive), 2);
+        assert_eq!(array.count_neighbors8_where(0, 2, &alive), 1);
+        assert_eq!(array.count_neighbors8_where(1, 1, |_| true), 8);
+        assert_eq!(array.count_neighbors8_where(0, 0, |_| true), 3);
+        
+        assert_eq!(Array2::from_elem(1, 1, 1u8).count_neighbors8_where(0, 0, |_| true), 0);
+    }
+    
+    #[test]
+    fn drop_elements_exactly_once() {
+        {
+            let mut array = Array2::from_fn(3, 2, || Tracked::new(0));
+            assert_eq!(live(), 6);
+            for e in array.iter_mut() {
+                *e = Tracked::new(1);
+            }
+            assert_eq!(live(), 6);
+            array.resize_with(4, 1, |_, _| Tracked::new(2));
+            assert_eq!(live(), 4);
+            array.resize_with(2, 3, |_, _| Tracked::new(3));
+            assert_eq!(live(), 6);
+            assert_eq!(array.extend_rows(vec![vec![Tracked::new(4), Tracked::new(4)]]), Ok(()));
+            assert_eq!(live(), 8);
+            assert_eq!(array.extend_rows(vec![vec![Tracked::new(5)]]), Err(()));
+            assert_eq!(live(), 8);
+        }
+        assert_eq!(live(), 0);
+        
+        {
+            let array = Array2::from_fn(2, 2, || Tracked::new(0));
+            let mut iter = array.into_iter();
+            let first = iter.next();
+            assert_eq!(live(), 4);
+            drop(iter);
+            assert_eq!(live(), 1);
+            drop(first);
+        }
+        assert_eq!(live(), 0);
+        
+        {
+            let mut array = Array2::from_fn(3, 3, || TrackedZst::new());
+            assert_eq!(live(), 9);
+            array.resize_with(2, 2, |_, _| TrackedZst::new());
+            assert_eq!(live(), 4);
+            let mut iter = array.into_iter();
+            let _first = iter.next();
+            assert_eq!(live(), 4);
+        }
+        assert_eq!(live(), 0);
+        
+        drop(Array2::from_fn(0, 3, || Tracked::new(0)));
+        assert_eq!(live(), 0);
+    }
+    
+    #[test]
+    fn clamped_rect() {
+        let array = Array2::from_elem(4, 3, 0u8);
+        assert_eq!(array.clamped_rect(0, 0, 4, 3), Some((0, 0, 4, 3)));
+        assert_eq!(array.clamped_rect(1, 1, 2, 1), Some((1, 1, 2, 1)));
+        assert_eq!(array.clamped_rect(3, 0, 10, 1), Some((3, 0, 1, 1)));
+        assert_eq!(array.clamped_rect(2, 1, 10, 10), Some((2, 1, 2, 2)));
+        assert_eq!(array.clamped_rect(1, 1, 0, 0), Some((1, 1, 0, 0)));
+        assert_eq!(array.clamped_rect(4, 0, 1, 1), None);
+        assert_eq!(array.clamped_rect(0, 3, 1, 1), None);
+        assert_eq!(zero_width_array().clamped_rect(0, 0, 1, 1), None);
+        
+        let (_, _, width, height) = array.clamped_rect(2, 1, 10, 10).unwrap();
+        assert_eq!(array.view(2, 1, 10, 10).len(), height as usize);
+        assert!(array.view(2, 1, 10, 10).all(|row| row.len() == width as usize));
+    }
+    
+    #[test]
+    fn view_rows_enumerate() {
+        let mut n = 0u8;
+        let array = Array2::from_fn(4, 4, || { n += 1; n - 1 });
+        let mut iter = array.view_rows_enumerate(0, 1, 4, 2);
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next(), Some((1, &[4, 5, 6, 7][..])));
+        assert_eq!(iter.next(), Some((2, &[8, 9, 10, 11][..])));
+        assert_eq!(iter.next(), None);
+        
+        let rows: Vec<_> = array.view_rows_enumerate(2, 2, 5, 5).collect();
+        assert_eq!(rows, vec![(2, &[10, 11][..]), (3, &[14, 15][..])]);
+        assert_eq!(array.view_rows_enumerate(0, 4, 1, 1).next(), None);
+    }
+    
+    #[test]
+    fn replace() {
+        let mut array = Array2::from_elem(2, 2, String::from("a"));
+        assert_eq!(array.replace((1, 0), String::from("b")), Some(String::from("a")));
+        assert_eq!(array.replace([1, 0], String::from("c")), Some(String::from("b")));
+        assert_eq!(array[(1, 0)], "c");
+        assert_eq!(array.replace((2, 0), String::from("d")), None);
+        assert_eq!(array.as_slice(), &["a", "c", "a", "a"][..]);
+    }
+    
+    #[test]
+    fn take() {
+        let mut array = Array2::from_elem(2, 2, Some(String::from("a")));
+        assert_eq!(array.take((0, 1)), Some(Some(String::from("a"))));
+        assert_eq!(array[(0, 1)], None);
+        assert_eq!(array.take((0, 1)), Some(None));
+        assert_eq!(array.take((0, 2)), None);
+        assert_eq!(array.iter().filter(|e| e.is_some()).count(), 3);
+    }
+    
+    #[test]
+    fn row_bands() {
+        let mut n = 0u8;
+        let array = Array2::from_fn(3, 5, || { n += 1; n - 1 });
+        let bands: Vec<_> = array.row_bands(2).collect();
+        assert_eq!(bands.iter().map(|band| band.len()).collect::<Vec<_>>(), vec![6, 6, 3]);
+        assert_eq!(bands[1], &[6, 7, 8, 9, 10, 11][..]);
+        assert_eq!(bands[2], &[12, 13, 14][..]);
+        
+        assert_eq!(array.row_bands(5).count(), 1);
+        assert_eq!(array.row_bands(1).count(), 5);
+        assert_eq!(zero_width_array().row_bands(1).next(), None);
+        assert_eq!(zero_height_array().row_bands(1).next(), None);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn row_bands_panic() {
+        standard_array().row_bands(0);
+    }
+    
+    #[test]
+    fn eq_slice() {
+        let array = standard_array();
+        assert!(array.eq_slice(&[0, 1, 2, 3]));
+        assert!(!array.eq_slice(&[0, 1, 2, 4]));
+        assert!(!array.eq_slice(&[0, 1, 2]));
+        assert!(Array2::from_nested_vec(vec![vec![0u8, 1, 2, 3]]).unwrap().eq_slice(&[0, 1, 2, 3]));
+        assert!(zero_width_array().eq_slice(&[]));
+    }
+    
+    #[test]
+    fn eq_nested() {
+        let array = standard_array();
+        assert!(array.eq_nested(&[vec![0, 1], vec![2, 3]]));
+        assert!(!array.eq_nested(&[vec![0, 1], vec![2, 4]]));
+        assert!(!array.eq_nested(&[vec![0, 1, 2, 3]]));
+        assert!(!array.eq_nested(&[vec![0, 1], vec![2, 3], vec![]]));
+        assert!(!array.eq_nested(&[vec![0, 1, 2], vec![3]]));
+        assert!(zero_width_array().eq_nested(&[vec![], vec![]]));
+        assert!(!zero_width_array().eq_nested(&[]));
+    }
+    
+    #[test]
+    fn from_fn_with_points() {
+        let array = Array2::from_fn_with_points(3, 2, |x, y| x + y * 10);
+        assert_eq!(array.as_slice(), &[0, 1, 2, 10, 11, 12]);
+        assert_eq!(Array2::from_fn_with_points(0, 2, |x, y| x + y).height(), 2);
+    }
+    
+    #[test]
+    fn scale_nearest() {
+        let array = standard_array();
+        let scaled = array.scale_nearest(2, 2);
+        assert_eq!(scaled.to_nested_vec(), vec![vec![0, 0, 1, 1],
+                                                vec![0, 0, 1, 1],
+                                                vec![2, 2, 3, 3],
+                                                vec![2, 2, 3, 3]]);
+        
+        let scaled = array.scale_nearest(3, 1);
+        assert_eq!(scaled.to_nested_vec(), vec![vec![0, 0, 0, 1, 1, 1], vec![2, 2, 2, 3, 3, 3]]);
+        
+        assert_eq!(array.scale_nearest(1, 1), array);
+        let scaled = array.scale_nearest(0, 2);
+        assert_eq!((scaled.width(), scaled.height()), (0, 4));
+        let scaled = array.scale_nearest(0, 0);
+        assert_eq!((scaled.width(), scaled.height()), (0, 0));
+    }
+    
+    #[test]
+    fn downsample() {
+        let mut n = 0u32;
+        let array = Array2::from_fn(4, 4, || { n += 1; n - 1 });
+        let sum = |block: &[&u32]| block.iter().map(|&&e| e).sum();
+        let reduced = array.downsample(2, 2, &sum);
+        assert_eq!(reduced.to_nested_vec(), vec![vec![0 + 1 + 4 + 5, 2 + 3 + 6 + 7],
+                                                 vec![8 + 9 + 12 + 13, 10 + 11 + 14 + 15]]);
+        
+        let reduced = array.downsample(3, 4, |block| block.len() as u32);
+        assert_eq!(reduced.to_nested_vec(), vec![vec![12, 4]]);
+        
+        let reduced = array.downsample(3, 3, |block| **block.iter().max().unwrap());
+        assert_eq!(reduced.to_nested_vec(), vec![vec![10, 11], vec![14, 15]]);
+        
+        assert_eq!(array.downsample(1, 1, |block| *block[0]), array);
+        let reduced = zero_width_array().downsample(2, 2, |_| unreachable!());
+        assert_eq!((reduced.width(), reduced.height()), (0, 1));
+    }
+    
+    #[test]
+    fn get_zst() {
+        let mut array = Array2::from_elem(5, 3, ZeroSizedType);
+        for &(x, y) in [(0, 0), (4, 0), (0, 2), (2, 1), (4, 2)].iter() {
+            assert_eq!(array.get(x, y), Some(&ZeroSizedType));
+            assert_eq!(array.get_mut(x, y), Some(&mut ZeroSizedType));
+            assert_eq!(array.at((x, y)), &ZeroSizedType);
+        }
+        for &(x, y) in [(5, 0), (0, 3), (5, 3), (::std::u32::MAX, 0)].iter() {
+            assert_eq!(array.get(x, y), None);
+            assert_eq!(array.get_mut(x, y), None);
+        }
+        
+        let mut array = Array2::from_elem(0, 3, ZeroSizedType);
+        assert_eq!(array.get(0, 0), None);
+        assert_eq!(array.get_mut(0, 0), None);
+    }
+    
+    #[test]
+    fn from_str_map() {
+        let array = Array2::from_str_map("#.#\n..#\n\n", |c| c == '#').unwrap();
+        assert_eq!(array.to_nested_vec(), vec![vec![true, false, true], vec![false, false, true]]);
+        
+        let array = Array2::from_str_map("ab\r\ncd", |c| c).unwrap();
+        assert_eq!(array.to_nested_vec(), vec![vec!['a', 'b'], vec!['c', 'd']]);
+        
+        let array = Array2::from_str_map("", |c| c).unwrap();
+        assert_eq!((array.width(), array.height()), (0, 0));
+    }
+    
+    #[test]
+    fn from_str_map_ragged() {
+        assert_eq!(Array2::from_str_map("#.#\n..\n", |c| c == '#'), None);
+        assert_eq!(Array2::from_str_map("#.\n\n.#", |c| c == '#'), None);
+    }
+    
+    #[test]
+    fn to_string_map() {
+        let map = "#.#\n..#";
+        let array = Array2::from_str_map(map, |c| c == '#').unwrap();
+        let rendered = array.to_string_map(|&wall| if wall { '#' } else { '.' });
+        assert_eq!(rendered, map);
+        assert_eq!(Array2::from_str_map(&rendered, |c| c == '#').unwrap(), array);
+        
+        assert_eq!(zero_width_array().to_string_map(|_| 'x'), "");
+        assert_eq!(zero_height_array().to_string_map(|_| 'x'), "");
+        assert_eq!(Array2::from_elem(1, 1, 0u8).to_string_map(|_| 'x'), "x");
+    }
+    
+    #[test]
+    fn rect() {
+        let mut n = 0u8;
+        let array = Array2::from_fn(4, 3, || { n += 1; n - 1 });
+        assert!(array.rect(1..3, 0..2).eq(array.view(1, 0, 2, 2)));
+        assert!(array.rect(2..9, 1..9).eq(array.view(2, 1, 7, 8)));
+        let rows: Vec<_> = array.rect(1..3, 0..2).collect();
+        assert_eq!(rows, vec![&[1, 2][..], &[5, 6][..]]);
+        
+        assert_eq!(array.rect(2..2, 0..2).next(), None);
+        assert_eq!(array.rect(0..2, 1..1).next(), None);
+        assert_eq!(array.rect(3..1, 0..2).next(), None);
+        assert_eq!(array.rect(0..2, 2..0).next(), None);
+        assert_eq!(array.rect(4..6, 0..2).next(), None);
+    }
+    
+    #[test]
+    fn rect_mut() {
+        let mut array = Array2::from_elem(4, 3, 0u8);
+        for row in array.rect_mut(1..3, 1..3) {
+            for e in row.iter_mut() {
+                *e = 1;
+            }
+        }
+        assert_eq!(array.to_nested_vec(), vec![vec![0, 0, 0, 0], vec![0, 1, 1, 0], vec![0, 1, 1, 0]]);
+        assert_eq!(array.rect_mut(3..1, 0..2).next(), None);
+        assert_eq!(array.rect_mut(0..0, 0..2).next(), None);
+    }
+    
+    #[test]
+    fn index_of() {
+        let array = Array2::from_nested_vec(vec![vec![0u8, 1, 2], vec![3, 1, 5]]).unwrap();
+        assert_eq!(array.index_of(&1), Some((1, 0)));
+        assert_eq!(array.index_of(&3), Some((0, 1)));
+        assert_eq!(array.index_of(&5), Some((2, 1)));
+        assert_eq!(array.index_of(&4), None);
+        assert_eq!(zero_width_array().index_of(&0), None);
+    }
+    
+    #[test]
+    fn overwrite_row() {
+        let mut array = Array2::from_elem(3, 2, String::from("a"));
+        let row = vec![String::from("b"), String::from("c"), String::from("d")];
+        assert_eq!(array.overwrite_row(1, row), Ok(()));
+        assert_eq!(array.as_slice(), &["a", "a", "a", "b", "c", "d"][..]);
+        
+        assert_eq!(array.overwrite_row(0, vec![String::from("e"), String::from("f")]), Err(()));
+        assert_eq!(array.overwrite_row(0, ::std::iter::repeat(String::from("e"))), Err(()));
+        assert_eq!(array.overwrite_row(2, vec![String::new(); 3]), Err(()));
+        assert_eq!(array.as_slice(), &["a", "a", "a", "b", "c", "d"][..]);
+        
+        let mut array = zero_width_array();
+        assert_eq!(array.overwrite_row(1, vec![]), Ok(()));
+        assert_eq!(array.overwrite_row(1, vec![1]), Err(()));
+    }
+    
+    #[test]
+    fn dedup_rows() {
+        let array = Array2::from_nested_vec(vec![vec![0u8, 1], vec![2, 3], vec![2, 3], vec![0, 1]]).unwrap();
+        let deduped = array.dedup_rows();
+        assert_eq!(deduped.to_nested_vec(), vec![vec![0, 1], vec![2, 3], vec![0, 1]]);
+        
+        assert_eq!(standard_array().dedup_rows(), standard_array());
+        assert_eq!(Array2::from_elem(2, 3, 7u8).dedup_rows(), Array2::from_elem(2, 1, 7u8));
+        let deduped = zero_width_array().dedup_rows();
+        assert_eq!((deduped.width(), deduped.height()), (0, 1));
+    }
+    
+    #[test]
+    fn checked_view() {
+        let array = standard_array();
+        let mut iter = array.checked_view(1, 0, 5, 5).unwrap();
+        assert_eq!(iter.next(), Some(&[1][..]));
+        assert_eq!(iter.next(), Some(&[3][..]));
+        assert_eq!(iter.next(), None);
+        
+        assert!(array.checked_view(2, 0, 1, 1).is_none());
+        assert!(array.checked_view(0, 2, 1, 1).is_none());
+        assert!(array.checked_view(0, 0, 0, 1).is_none());
+        assert!(array.checked_view(0, 0, 1, 0).is_none());
+        assert!(zero_width_array().checked_view(0, 0, 1, 1).is_none());
+        
+        let mut array = standard_array();
+        assert_eq!(array.checked_view_mut(0, 1, 2, 1).unwrap().next(), Some(&mut [2, 3][..]));
+        assert!(array.checked_view_mut(2, 0, 1, 1).is_none());
+        assert!(array.checked_view_mut(0, 2, 1, 1).is_none());
+        assert!(array.checked_view_mut(0, 0, 0, 1).is_none());
+        assert!(array.checked_view_mut(0, 0, 1, 0).is_none());
+    }
+    
+    #[test]
+    fn dimensions() {
+        let array = Array2::from_elem(3, 2, 0u8);
+        assert_eq!(array.dimensions(), (array.width(), array.height()));
+        let (width, height) = array.dimensions();
+        assert_eq!((width, height), (3, 2));
+        assert_eq!(zero_width_array().dimensions(), (0, 2));
+        assert_eq!(zero_height_array().dimensions(), (2, 0));
+    }
+    
+    #[test]
+    fn into_transposed() {
+        let strings = |rows: Vec<Vec<&str>>| -> Vec<Vec<String>> {
+            rows.into_iter().map(|row| row.into_iter().map(String::from).collect()).collect()
+        };
+        let array = Array2::from_nested_vec(strings(vec![vec!["a", "b", "c"], vec!["d", "e", "f"]])).unwrap();
+        let transposed = array.into_transposed();
+        assert_eq!(transposed.dimensions(), (2, 3));
+        assert!(transposed.eq_nested(&strings(vec![vec!["a", "d"], vec!["b", "e"], vec!["c", "f"]])));
+        
+        {
+            let array = Array2::from_fn(3, 2, || Tracked::new(0));
+            let transposed = array.into_transposed();
+            assert_eq!(live(), 6);
+            assert_eq!(transposed.dimensions(), (2, 3));
+        }
+        assert_eq!(live(), 0);
+        
+        assert_eq!(zero_width_array().into_transposed().dimensions(), (2, 0));
+        assert_eq!(zst_array().into_transposed(), zst_array());
+    }
+    
+    #[test]
+    fn row_binary_search() {
+        let array = Array2::from_nested_vec(vec![vec![9u8, 3, 1, 0], vec![1, 3, 5, 7]]).unwrap();
+        assert_eq!(array.row_binary_search(1, &5), Ok(2));
+        assert_eq!(array.row_binary_search(1, &1), Ok(0));
+        assert_eq!(array.row_binary_search(1, &4), Err(2));
+        assert_eq!(array.row_binary_search(1, &8), Err(4));
+        assert_eq!(array.row_binary_search(1, &0), Err(0));
+    }
+    
+    #[test]
+    #[should_panic]
+    fn row_binary_search_panic() {
+        standard_array().row_binary_search(2, &0).ok();
+    }
+    
+    #[test]
+    fn split_cell_neighbors4() {
+        let mut n = 0u8;
+        let mut array = Array2::from_fn(3, 3, || { n += 1; n - 1 });
+        {
+            let (center, mut neighbors) = array.split_cell_neighbors4(1, 1).unwrap();
+            assert_eq!(*center, 4);
+            let values: Vec<_> = neighbors.iter().map(|n| n.as_ref().map(|e| **e)).collect();
+            assert_eq!(values, vec![Some(1), Some(5), Some(7), Some(3)]);
+            for neighbor in neighbors.iter_mut() {
+                if let Some(ref mut e) = *neighbor {
+                    **e += *center;
+                }
+            }
+            *center = 0;
+        }
+        assert_eq!(array.to_nested_vec(), vec![vec![0, 5, 2], vec![7, 0, 9], vec![6, 11, 8]]);
+        
+        {
+            let (center, neighbors) = array.split_cell_neighbors4(0, 0).unwrap();
+            assert_eq!(*center, 0);
+            let values: Vec<_> = neighbors.iter().map(|n| n.as_ref().map(|e| **e)).collect();
+            assert_eq!(values, vec![None, Some(5), Some(7), None]);
+        }
+        {
+            let (_, neighbors) = array.split_cell_neighbors4(2, 2).unwrap();
+            assert_eq!(neighbors.iter().filter(|n| n.is_some()).count(), 2);
+        }
+        assert!(array.split_cell_neighbors4(3, 0).is_none());
+        assert!(array.split_cell_neighbors4(0, 3).is_none());
+        
+        let mut array = Array2::from_elem(1, 1, 0u8);
+        assert!(array.split_cell_neighbors4(0, 0).unwrap().1.iter().all(|n| n.is_none()));
+    }
+    
+    #[test]
+    fn from_checkerboard() {
+        let array = Array2::from_checkerboard(3, 3, 'a', 'b');
+        assert_eq!(array.to_nested_vec(), vec![vec!['a', 'b', 'a'], vec!['b', 'a', 'b'], vec!['a', 'b', 'a']]);
+        
+        let array = Array2::from_checkerboard(4, 2, 1u8, 0);
+        assert_eq!(array.to_nested_vec(), vec![vec![1, 0, 1, 0], vec![0, 1, 0, 1]]);
+        assert_eq!(Array2::from_checkerboard(0, 2, 1u8, 0), zero_width_array());
+    }
+    
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_rows_mut() {
+        let mut n = 0u32;
+        let mut array = Array2::from_fn(64, 32, || { n += 1; n - 1 });
+        let mut expected = Array2::from_fn(64, 32, || 0u32);
+        for (dst, src) in expected.iter_mut().zip(array.iter()) {
+            *dst = *src + 1;
+        }
+        array.par_rows_mut(|row| for e in row.iter_mut() { *e += 1; });
+        assert_eq!(array, expected);
+        
+        let mut array = zero_width_array();
+        array.par_rows_mut(|_| unreachable!());
+    }
+    
+    #[test]
+    fn blend_from() {
+        let mut array = Array2::from_elem(4, 3, 1u8);
+        let src = standard_array();
+        array.blend_from(1, 1, &src, |dest, src| *dest += *src);
+        assert_eq!(array.to_nested_vec(), vec![vec![1, 1, 1, 1], vec![1, 1, 2, 1], vec![1, 3, 4, 1]]);
+        
+        array.blend_from(3, 2, &src, |dest, src| *dest = cmp::max(*dest, *src + 10));
+        assert_eq!(array.to_nested_vec(), vec![vec![1, 1, 1, 1], vec![1, 1, 2, 1], vec![1, 3, 4, 10]]);
+        
+        array.blend_from(4, 0, &src, |_, _| unreachable!());
+        array.blend_from(0, 0, &zero_width_array(), |_, _| unreachable!());
+    }
+    
+    #[test]
+    fn try_into_square() {
+        assert_eq!(standard_array().try_into_square(), Ok(standard_array()));
+        assert_eq!(Array2::from_elem(0, 0, 0u8).try_into_square(), Ok(Array2::from_elem(0, 0, 0u8)));
+        assert_eq!(zero_width_array().try_into_square(), Err(zero_width_array()));
+        assert_eq!(Array2::from_elem(3, 2, 0u8).try_into_square(), Err(Array2::from_elem(3, 2, 0u8)));
+    }
+    
+    #[test]
+    fn square_array() {
+        let mut square = SquareArray2::new(standard_array()).ok().unwrap();
+        assert_eq!(square.side(), 2);
+        assert_eq!(square[(1, 0)], 1);
+        square.transpose_in_place();
+        assert_eq!(square.as_slice(), &[0, 2, 1, 3][..]);
+        
+        square[(1, 0)] = 4;
+        *square.at_mut((0, 1)) = 5;
+        *square.get_mut(1, 1).unwrap() += 1;
+        assert!(square.get_mut(2, 0).is_none());
+        assert_eq!(square.as_slice(), &[0, 4, 5, 4][..]);
+        for e in square.iter_mut() {
+            *e *= 2;
+        }
+        square.as_slice_mut()[0] = 1;
+        assert_eq!(square.as_slice(), &[1, 8, 10, 8][..]);
+        assert_eq!(square.into_inner().dimensions(), (2, 2));
+        
+        assert_eq!(SquareArray2::new(zero_height_array()).err(), Some(zero_height_array()));
+    }
+    
+    #[test]
+    fn summed_area_table() {
+        // Array:
+        // [1, 2, 3]
+        // [4, 5, 6]
+        let array = Array2::from_nested_vec(vec![vec![1u8, 2, 3], vec![4, 5, 6]]).unwrap();
+        let table = array.summed_area_table();
+        assert_eq!(table.to_nested_vec(), vec![vec![1, 3, 6], vec![5, 12, 21]]);
+        assert_eq!(zero_width_array().summed_area_table().dimensions(), (0, 2));
+    }
+    
+    #[test]
+    fn rect_sum_queries() {
+        let mut n = 0u32;
+        let array = Array2::from_fn(5, 4, || { n += 7; n % 11 });
+        let table = array.summed_area_table();
+        let brute_force = |x, y, w, h| -> u64 {
+            array.view(x, y, w, h).flat_map(|row| row.iter()).map(|&e| e as u64).sum()
+        };
+        for &(x, y, w, h) in [(0, 0, 5, 4), (1, 1, 2, 2), (2, 0, 1, 4), (3, 2, 9, 9), (0, 3, 5, 1), (4, 3, 1, 1)].iter() {
+            assert_eq!(rect_sum(&table, x, y, w, h), brute_force(x, y, w, h));
+        }
+        assert_eq!(rect_sum(&table, 5, 0, 1, 1), 0);
+        assert_eq!(rect_sum(&table, 1, 1, 0, 2), 0);
+    }
+    
+    #[test]
+    fn iter_mut_rev() {
+        let mut array = standard_array();
+        {
+            let mut iter = array.iter_mut();
+            let a = iter.next().unwrap();
+            let b = iter.next_back().unwrap();
+            let c = iter.next_back().unwrap();
+            let d = iter.next().unwrap();
+            assert_eq!(iter.next(), None);
+            assert_eq!(iter.next_back(), None);
+            assert_eq!((*a, *b, *c, *d), (0, 3, 2, 1));
+            *a += 10;
+            *b += 20;
+            *c += 30;
+            *d += 40;
+        }
+        assert_eq!(array.as_slice(), &[10, 41, 32, 23][..]);
+        
+        let reversed: Vec<_> = array.iter_mut().rev().map(|e| *e).collect();
+        assert_eq!(reversed, vec![23, 32, 41, 10]);
+        
+        let mut array = zst_array();
+        let mut iter = array.iter_mut();
+        assert_eq!(iter.next_back(), Some(&mut ZeroSizedType));
+        assert_eq!(iter.by_ref().count(), 3);
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(zero_width_array().iter_mut().next_back(), None);
+    }
+    
+    #[test]
+    fn map_rows() {
+        let array = Array2::from_nested_vec(vec![vec![1u8, 2, 3], vec![4, 5, 6]]).unwrap();
+        let pairs = array.map_rows(|row| row.windows(2).map(|w| w[0] + w[1]).collect()).unwrap();
+        assert_eq!(pairs.dimensions(), (2, 2));
+        assert_eq!(pairs.to_nested_vec(), vec![vec![3, 5], vec![9, 11]]);
+        
+        let strings = array.map_rows(|row| row.iter().map(|e| e.to_string()).collect()).unwrap();
+        assert_eq!(strings.as_slice(), &["1", "2", "3", "4", "5", "6"][..]);
+        
+        let widened = zero_width_array().map_rows(|_| vec![0u8; 3]).unwrap();
+        assert_eq!(widened.dimensions(), (3, 2));
+    }
+    
+    #[test]
+    fn map_rows_ragged() {
+        let array = Array2::from_nested_vec(vec![vec![1u8, 2, 3], vec![4, 5, 6]]).unwrap();
+        assert!(array.map_rows(|row| row.iter().filter(|&&e| e % 2 == 0).cloned().collect()).is_none());
+    }
+    
+    #[test]
+    fn histogram() {
+        let array = Array2::from_str_map("#..#\n.~~.\n#..#", |c| c).unwrap();
+        let histogram = array.histogram();
+        assert_eq!(histogram.len(), 3);
+        assert_eq!(histogram[&'#'], 4);
+        assert_eq!(histogram[&'.'], 6);
+        assert_eq!(histogram[&'~'], 2);
+        assert_eq!(histogram.get(&'x'), None);
+        
+        assert!(zero_width_array().histogram().is_empty());
+    }
+    
+    #[test]
+    fn is_border_and_corner() {
+        let array = Array2::from_elem(3, 3, 0u8);
+        assert!(array.is_border(0, 0) && array.is_corner(0, 0));
+        assert!(array.is_border(2, 2) && array.is_corner(2, 2));
+        assert!(array.is_border(2, 0) && array.is_corner(2, 0));
+        assert!(array.is_border(1, 0) && !array.is_corner(1, 0));
+        assert!(array.is_border(0, 1) && !array.is_corner(0, 1));
+        assert!(!array.is_border(1, 1) && !array.is_corner(1, 1));
+        assert!(!array.is_border(3, 0) && !array.is_corner(3, 0));
+        assert!(!array.is_border(0, 3) && !array.is_corner(0, 3));
+        
+        let array = Array2::from_elem(1, 1, 0u8);
+        assert!(array.is_border(0, 0) && array.is_corner(0, 0));
+        
+        let array = Array2::from_elem(4, 3, 0u8);
+        for (x, y, _) in array.iter().with_points() {
+            assert_eq!(array.is_border(x, y), array.border().any(|(bx, by, _)| (bx, by) == (x, y)));
+        }
+        assert!(!zero_width_array().is_border(0, 0));
+    }
+    
+    #[test]
+    fn rows_nth() {
+        let mut n = 0;
+        let array = Array2::from_fn(3, 2, || { n += 1; n - 1 });
+        assert_eq!(array.rows().nth(0), Some(&[0, 1, 2][..]));
+        assert_eq!(array.rows().nth(1), Some(&[3, 4, 5][..]));
+        assert_eq!(array.rows().nth(2), None);
+        
+        let mut iter = array.rows();
+        assert_eq!(iter.nth(5), None);
+        assert_eq!(iter.next(), None);
+        
+        let mut iter = array.rows();
+        iter.next();
+        assert_eq!(iter.nth(0), Some(&[3, 4, 5][..]));
+        assert_eq!(iter.next(), None);
+        
+        let mut n = 0;
+        let mut array = Array2::from_fn(3, 2, || { n += 1; n - 1 });
+        array.rows_mut().nth(1).unwrap()[0] = 9;
+        assert_eq!(array.as_slice(), &[0, 1, 2, 9, 4, 5]);
+        assert!(array.rows_mut().nth(2).is_none());
+        
+        assert_eq!(zero_width_array().rows().nth(0), None);
+        let array = zst_array();
+        assert_eq!(array.rows().nth(1).map(|row| row.len()), Some(array.dimensions().0 as usize));
+        assert_eq!(array.rows().nth(array.dimensions().1 as usize), None);
+    }
+    
+    #[test]
+    fn new_uninit() {
+        let mut array = Array2::<u32>::new_uninit(3, 2);
+        assert_eq!(array.dimensions(), (3, 2));
+        for (i, e) in array.as_slice_mut().iter_mut().enumerate() {
+            *e = MaybeUninit::new(i as u32 * 2);
+        }
+        let array = unsafe { array.assume_init() };
+        assert_eq!(array.as_slice(), &[0, 2, 4, 6, 8, 10]);
+        
+        {
+            let mut array = Array2::<Tracked>::new_uninit(2, 2);
+            for (x, y, e) in array.iter_mut().with_points() {
+                *e = MaybeUninit::new(Tracked::new((x + y * 2) as u8));
+            }
+            assert_eq!(live(), 4);
+            let array = unsafe { array.assume_init() };
+            assert_eq!(array[(1, 1)].0, 3);
+        }
+        assert_eq!(live(), 0);
+        
+        let array = unsafe { Array2::<u64>::new_uninit(0, 5).assume_init() };
+        assert_eq!(array.dimensions(), (0, 5));
+        let mut array = Array2::<ZeroSizedType>::new_uninit(2, 2);
+        for e in array.iter_mut() {
+            *e = MaybeUninit::new(ZeroSizedType);
+        }
+        assert_eq!(unsafe { array.assume_init() }.iter().count(), 4);
+    }
+    
+    #[test]
+    fn mask() {
+        let mut n = 0u8;
+        let array = Array2::from_fn(3, 2, || { n += 1; n - 1 });
+        let mask = array.mask(|&e| e % 2 == 0);
+        assert_eq!(mask.dimensions(), (3, 2));
+        assert_eq!(mask.as_slice(), &[true, false, true, false, true, false]);
+        
+        let mask = zero_height_array().mask(|_| true);
+        assert_eq!(mask.dimensions(), zero_height_array().dimensions());
+    }
+    
+    #[test]
+    fn apply_mask() {
+        let mut n = 0u8;
+        let mut array = Array2::from_fn(3, 2, || { n += 1; n - 1 });
+        let mask = array.mask(|&e| e % 2 == 0);
+        assert_eq!(array.apply_mask(&mask, 9), Ok(()));
+        assert_eq!(array.as_slice(), &[9, 1, 9, 3, 9, 5]);
+        
+        let mask = Array2::from_elem(2, 3, true);
+        assert_eq!(array.apply_mask(&mask, 0), Err(()));
+        assert_eq!(array.as_slice(), &[9, 1, 9, 3, 9, 5]);
+    }
+    
+    #[test]
+    fn view_rotated() {
+        // Array:
+        // 0  1  2  3
+        // 4  5  6  7
+        // 8  9  10 11
+        let mut n = 0u8;
+        let array = Array2::from_fn(4, 3, || { n += 1; n - 1 });
+        let collect = |turns| array.view_rotated(1, 1, 2, 2, turns).cloned().collect::<Vec<_>>();
+        assert_eq!(collect(0), vec![5, 6, 9, 10]);
+        assert_eq!(collect(1), vec![9, 5, 10, 6]);
+        assert_eq!(collect(2), vec![10, 9, 6, 5]);
+        assert_eq!(collect(3), vec![6, 10, 5, 9]);
+        assert_eq!(collect(4), collect(0));
+        
+        // Clipped to the 2x3 section at (2, 0), which becomes 3x2 after one turn.
+        let rotated = array.view_rotated(2, 0, 5, 5, 1);
+        assert_eq!(rotated.len(), 6);
+        assert_eq!(rotated.cloned().collect::<Vec<_>>(), vec![10, 6, 2, 11, 7, 3]);
+        assert_eq!(array.view_rotated(4, 0, 1, 1, 1).next(), None);
+        assert_eq!(zero_width_array().view_rotated(0, 0, 1, 1, 2).next(), None);
+    }
+    
+    #[test]
+    fn shrink() {
+        let mut n = 0u8;
+        let array = Array2::from_fn(5, 5, || { n += 1; n - 1 });
+        let shrunk = array.shrink(1);
+        assert_eq!(shrunk.dimensions(), (3, 3));
+        assert_eq!(shrunk.as_slice(), &[6, 7, 8, 11, 12, 13, 16, 17, 18]);
+        assert_eq!(array.shrink(0).as_slice(), array.as_slice());
+        assert_eq!(array.shrink(2).as_slice(), &[12]);
+        assert_eq!(array.shrink(3).dimensions(), (0, 0));
+        assert_eq!(array.shrink(::std::u32::MAX).dimensions(), (0, 0));
+        
+        let array = Array2::from_elem(6, 2, 0u8);
+        assert_eq!(array.shrink(1).dimensions(), (0, 0));
+    }
+    
+    #[test]
+    fn diagonals() {
+        // Array:
+        // 0 1 2
+        // 3 4 5
+        let mut n = 0u8;
+        let array = Array2::from_fn(3, 2, || { n += 1; n - 1 });
+        let diagonals = array.diagonals().collect::<Vec<_>>();
+        assert_eq!(diagonals.len(), 4);
+        assert_eq!(diagonals[0], vec![&3]);
+        assert_eq!(diagonals[1], vec![&0, &4]);
+        assert_eq!(diagonals[2], vec![&1, &5]);
+        assert_eq!(diagonals[3], vec![&2]);
+        assert_eq!(diagonals.iter().map(|d| d.len()).sum::<usize>(), 6);
+        
+        assert_eq!(zero_width_array().diagonals().len(), 0);
+        assert_eq!(zero_height_array().diagonals().next(), None);
+    }
+    
+    #[test]
+    fn as_strided() {
+        let array = Array2::from_elem(3, 2, 0u8);
+        let (data, stride) = array.as_strided();
+        assert_eq!(data.len(), 6);
+        assert_eq!(stride, 3);
+        assert_eq!(zero_height_array().as_strided().0.len(), 0);
+    }
+    
+    #[test]
+    fn sort_rows_by() {
+        let mut array = Array2::from_nested_vec(vec![vec![3, 0], vec![1, 1], vec![2, 2]]).unwrap();
+        array.sort_rows_by(|a, b| a[0].cmp(&b[0]));
+        assert_eq!(array.dimensions(), (2, 3));
+        assert_eq!(array.as_slice(), &[1, 1, 2, 2, 3, 0]);
+        
+        // Rows with equal keys keep their relative order.
+        let mut array = Array2::from_nested_vec(vec![vec![1, 0], vec![0, 1], vec![1, 2], vec![0, 3]]).unwrap();
+        array.sort_rows_by(|a, b| a[0].cmp(&b[0]));
+        assert_eq!(array.as_slice(), &[0, 1, 0, 3, 1, 0, 1, 2]);
+        
+        let mut array = Array2::from_nested_vec(vec![vec![2], vec![3], vec![1], vec![5], vec![4]]).unwrap();
+        array.sort_rows_by(|a, b| b[0].cmp(&a[0]));
+        assert_eq!(array.as_slice(), &[5, 4, 3, 2, 1]);
+        
+        let mut array = zero_width_array();
+        array.sort_rows_by(|a, b| a.len().cmp(&b.len()));
+        assert_eq!(array.dimensions(), zero_width_array().dimensions());
+    }
+    
+    #[test]
+    fn build() {
+        let mut n = 0u8;
+        let array = Array2::build(3, 2, Init::With(&mut || { n += 1; n - 1 })).unwrap();
+        assert_eq!(array.as_slice(), &[0, 1, 2, 3, 4, 5]);
+        let array = Array2::build(2, 3, Init::Copy(array.as_slice())).unwrap();
+        assert_eq!(array.dimensions(), (2, 3));
+        assert_eq!(array.as_slice(), &[0, 1, 2, 3, 4, 5]);
+        
+        let mut calls = 0;
+        let result = Array2::<u8>::build(::std::u32::MAX, ::std::u32::MAX, Init::With(&mut || { calls += 1; 0 }));
+        assert_eq!(result.err(), Some(BuildError::Overflow));
+        assert_eq!(calls, 0);
+        assert_eq!(Array2::<u64>::build(::std::u32::MAX, ::std::u32::MAX / 2, Init::Uninit).err(), Some(BuildError::Overflow));
+        
+        // Arrays without an allocation still have an aligned pointer.
+        let array = Array2::<u64>::build(0, 4, Init::Uninit).unwrap();
+        assert_eq!(array.as_slice().as_ptr() as usize % mem::align_of::<u64>(), 0);
+        assert_eq!(Array2::<ZeroSizedType>::build(3, 3, Init::With(&mut || ZeroSizedType)).unwrap().iter().count(), 9);
+    }
+    
+    #[test]
+    #[should_panic]
+    fn from_fn_overflow() {
+        Array2::from_fn(::std::u32::MAX, ::std::u32::MAX, || 0u8);
+    }
+    
+    #[test]
+    fn decode() {
+        let array = Array2::from_nested_vec(vec![vec![1u8, 2, 3], vec![4, 5, 6]]).unwrap();
+        let decoded: Array2<u8> = json::decode(&json::encode(&array).unwrap()).unwrap();
+        assert_eq!(decoded.dimensions(), (3, 2));
+        assert_eq!(decoded.as_slice(), array.as_slice());
+        
+        let decoded: Array2<u64> = json::decode(r#"{"width":0,"height":2,"data":[]}"#).unwrap();
+        assert_eq!(decoded.dimensions(), (0, 2));
+        assert!(json::decode::<Array2<u8>>(r#"{"width":2,"height":2,"data":[1,2,3]}"#).is_err());
+        assert!(json::decode::<Array2<String>>(r#"{"width":2,"height":1,"data":["a",2]}"#).is_err());
+    }
+    
+    #[test]
+    fn view_clone() {
+        let mut n = 0u8;
+        let array = Array2::from_fn(4, 3, || { n += 1; n - 1 });
+        let mut view = array.view(1, 1, 2, 5);
+        assert_eq!(view.clone().count(), 2);
+        assert_eq!(view.next(), Some(&[5, 6][..]));
+        
+        let clone = view.clone();
+        assert_eq!(clone.collect::<Vec<_>>(), vec![&[9, 10][..]]);
+        assert_eq!(view.next(), Some(&[9, 10][..]));
+        assert_eq!(view.next(), None);
+    }
+    
+    #[test]
+    fn pixels_mut() {
+        let mut array = Array2::from_elem(3, 2, 0u8);
+        for (x, y, c) in array.pixels_mut() {
+            *c = x as u8 + y as u8 * 10;
+        }
+        assert_eq!(array.as_slice(), &[0, 1, 2, 10, 11, 12]);
+        assert_eq!(zero_width_array().pixels_mut().next(), None);
+    }
+    
+    #[test]
+    fn count() {
+        let mut array = Array2::from_elem(3, 4, 0u8);
+        assert_eq!(array.iter().count(), 12);
+        let mut iter = array.iter();
+        iter.nth(4);
+        assert_eq!(iter.count(), 7);
+        let mut iter = array.rows();
+        iter.next();
+        assert_eq!(iter.count(), 3);
+        
+        let mut iter = array.iter_mut();
+        iter.next();
+        iter.next_back();
+        assert_eq!(iter.count(), 10);
+        let mut iter = array.rows_mut();
+        iter.nth(2);
+        assert_eq!(iter.count(), 1);
+        
+        assert_eq!(zero_width_array().rows().count(), 0);
+        assert_eq!(zero_height_array().iter().count(), 0);
+        let mut array = zst_array();
+        let mut iter = array.iter();
+        iter.next();
+        assert_eq!(iter.count(), 3);
+        assert_eq!(array.rows().count(), 2);
+        assert_eq!(array.rows_mut().skip(1).count(), 1);
+    }
+    
+    #[test]
+    fn grow_to_pow2() {
+        let mut n = 0u8;
+        let array = Array2::from_fn(3, 5, || { n += 1; n });
+        let grown = array.grow_to_pow2(0);
+        assert_eq!(grown.dimensions(), (4, 8));
+        for (x, y, &e) in grown.iter().with_points() {
+            assert_eq!(e, array.get(x, y).cloned().unwrap_or(0));
+        }
+        assert_eq!(grown.iter().filter(|&&e| e == 0).count(), 32 - 15);
+        
+        let array = Array2::from_elem(4, 2, 7u8);
+        let grown = array.grow_to_pow2(0);
+        assert_eq!(grown.dimensions(), (4, 2));
+        assert_eq!(grown.as_slice(), array.as_slice());
+        assert_eq!(zero_width_array().grow_to_pow2(0).dimensions(), zero_width_array().dimensions());
+        assert_eq!(Array2::from_elem(1, 1, 1u8).grow_to_pow2(0).as_slice(), &[1]);
+    }
+    
+    #[test]
+    fn into_iter_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<IntoIter<String>>();
+        
+        let iter = Array2::from_elem(2, 2, String::from("a")).into_iter();
+        let joined = ::std::thread::spawn(move || iter.collect::<String>()).join().unwrap();
+        assert_eq!(joined, "aaaa");
+    }
 }